@@ -1,4 +1,7 @@
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     Number(i64),
     Identifier(String),
@@ -7,9 +10,16 @@ pub enum Expr {
         operator: Operator,
         right: Box<Expr>,
     },
+    /// A call to a function provided by the host program, such as one
+    /// registered with `Interpreter::register_function`.
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operator {
     Plus,
     Minus,
@@ -23,16 +33,57 @@ pub enum Operator {
     LtEq,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Type {
+    Integer,
+    Boolean,
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Integer => write!(f, "entero"),
+            Type::Boolean => write!(f, "booleano"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     pub statements: Vec<Statement>,
+    /// Source line of each statement, parallel to `statements`. Programs built
+    /// by hand may leave this empty.
+    pub lines: Vec<usize>,
+}
+
+impl Program {
+    pub fn line(&self, index: usize) -> Option<usize> {
+        self.lines.get(index).copied()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Statement {
     Assign {
         name: String,
         value: Expr,
     },
+    Const {
+        name: String,
+        value: Expr,
+    },
+    Declare {
+        name: String,
+        type_annotation: Option<Type>,
+        value: Option<Expr>,
+    },
+    CompoundAssign {
+        name: String,
+        operator: Operator,
+        value: Expr,
+    },
     Expr(Expr),
 }
\ No newline at end of file
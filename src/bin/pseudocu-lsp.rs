@@ -0,0 +1,14 @@
+use std::io;
+use pseudocu::lsp;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    match lsp::run(stdin.lock(), stdout.lock()) {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+pub mod python;
+pub mod c;
+pub mod js;
+pub mod asm;
+pub mod wat;
+pub mod flowchart;
+pub mod dot;
+pub mod mermaid;
+
+use crate::ast::{Expr, Operator};
+
+/// Whether an expression can only produce `0` or `1`, so storing it in a
+/// `booleano` variable needs no runtime check.
+pub(crate) fn is_always_boolean(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(value) => *value == 0 || *value == 1,
+        Expr::BinaryOp { operator, .. } => matches!(
+            operator,
+            Operator::Eq | Operator::NotEq | Operator::Gt | Operator::Lt
+            | Operator::GtEq | Operator::LtEq
+        ),
+        Expr::Identifier(_) | Expr::Call { .. } => false,
+    }
+}
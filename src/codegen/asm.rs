@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use super::is_always_boolean;
+
+const INDENT: &str = "    ";
+
+/// Translates a program into x86-64 assembly for Linux, in GNU `as` (AT&T)
+/// syntax.
+///
+/// The output does not link against libc: it talks to the kernel through
+/// `write` and `exit` system calls, so it can be built with
+/// `as -o program.o program.s && ld -o program program.o`. Variables live in
+/// `.bss`, expressions are evaluated in `%rax` using the machine stack for
+/// intermediate values, and overflow, division by zero, `booleano`
+/// mismatches and calls to functions, which only a host can provide, exit
+/// with status 1 after printing the interpreter's `Runtime error` message on
+/// stderr. On success the program prints the variable table in the same
+/// sorted format as `Interpreter::print_variables`.
+pub fn generate(program: &Program) -> String {
+    let mut generator = AsmGenerator::default();
+    for statement in &program.statements {
+        generator.statement(statement);
+    }
+    generator.finish()
+}
+
+#[derive(Default)]
+struct AsmGenerator {
+    body: Vec<String>,
+    /// Variable names mapped to their `.bss` slot, and whether a value is
+    /// ever stored in them.
+    variables: BTreeMap<String, (usize, bool)>,
+    booleans: Vec<String>,
+    /// `.rodata` strings, named `.Lstr<index>`.
+    strings: Vec<String>,
+    uses_overflow: bool,
+    uses_div: bool,
+    uses_bool: bool,
+}
+
+impl AsmGenerator {
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => self.assign(name, value),
+            Statement::Const { name, value } => self.assign(name, value),
+            Statement::Declare { name, type_annotation, value } => {
+                self.slot(name);
+                if *type_annotation == Some(Type::Boolean) {
+                    self.booleans.push(name.clone());
+                }
+                if let Some(value) = value {
+                    self.assign(name, value);
+                }
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let value = Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(name.clone())),
+                    operator: operator.clone(),
+                    right: Box::new(value.clone()),
+                };
+                self.assign(name, &value);
+            }
+            Statement::Expr(expr) => self.expr(expr),
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &Expr) {
+        self.expr(value);
+        if self.booleans.iter().any(|boolean| boolean == name) && !is_always_boolean(value) {
+            self.uses_bool = true;
+            let message = self.string(&format!(" to {} of type booleano\n", name));
+            self.emit("cmp $1, %rax");
+            self.emit("jbe 1f");
+            self.emit(&format!("lea {}(%rip), %rsi", message.0));
+            self.emit(&format!("mov ${}, %edx", message.1));
+            self.emit("jmp pc_booleano_error");
+            self.body.push("1:".to_string());
+        }
+        let slot = self.slot(name);
+        self.variables.get_mut(name).unwrap().1 = true;
+        self.emit(&format!("mov %rax, pc_var{}(%rip)", slot));
+    }
+
+    /// Emits code leaving the value of `expr` in `%rax`.
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(value) => self.emit(&format!("movabs ${}, %rax", value)),
+            Expr::Identifier(name) => {
+                let slot = self.slot(name);
+                self.emit(&format!("mov pc_var{}(%rip), %rax", slot));
+            }
+            Expr::BinaryOp { left, operator, right } => {
+                self.expr(left);
+                self.emit("push %rax");
+                self.expr(right);
+                self.emit("mov %rax, %rcx");
+                self.emit("pop %rax");
+                self.operator(operator);
+            }
+            Expr::Call { name, args } => {
+                // Generated programs have no host, so every call fails once
+                // its arguments have been evaluated.
+                for arg in args {
+                    self.expr(arg);
+                }
+                let message = self.string(&format!("Runtime error: Undefined function: {}\n", name));
+                self.emit(&format!("lea {}(%rip), %rsi", message.0));
+                self.emit(&format!("mov ${}, %edx", message.1));
+                self.emit("jmp pc_fail");
+            }
+        }
+    }
+
+    /// Emits `%rax = %rax <operator> %rcx`.
+    fn operator(&mut self, operator: &Operator) {
+        let set = match operator {
+            Operator::Plus | Operator::Minus | Operator::Multiply => {
+                self.uses_overflow = true;
+                let instruction = match operator {
+                    Operator::Plus => "add",
+                    Operator::Minus => "sub",
+                    _ => "imul",
+                };
+                self.emit(&format!("{} %rcx, %rax", instruction));
+                self.emit("jo pc_overflow");
+                return;
+            }
+            Operator::Divide => {
+                self.uses_overflow = true;
+                self.uses_div = true;
+                self.emit("test %rcx, %rcx");
+                self.emit("jz pc_division_by_zero");
+                // `idiv` faults on LLONG_MIN / -1, so negate instead.
+                self.emit("cmp $-1, %rcx");
+                self.emit("jne 1f");
+                self.emit("neg %rax");
+                self.emit("jo pc_overflow");
+                self.emit("jmp 2f");
+                self.body.push("1:".to_string());
+                self.emit("cqo");
+                self.emit("idiv %rcx");
+                self.body.push("2:".to_string());
+                return;
+            }
+            Operator::Eq => "sete",
+            Operator::NotEq => "setne",
+            Operator::Gt => "setg",
+            Operator::Lt => "setl",
+            Operator::GtEq => "setge",
+            Operator::LtEq => "setle",
+        };
+        self.emit("cmp %rcx, %rax");
+        self.emit(&format!("{} %al", set));
+        self.emit("movzbq %al, %rax");
+    }
+
+    fn emit(&mut self, instruction: &str) {
+        self.body.push(format!("{}{}", INDENT, instruction));
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        let next = self.variables.len();
+        self.variables.entry(name.to_string()).or_insert((next, false)).0
+    }
+
+    /// Adds a string to `.rodata`, returning its label and length in bytes.
+    fn string(&mut self, contents: &str) -> (String, usize) {
+        self.strings.push(contents.to_string());
+        (format!(".Lstr{}", self.strings.len() - 1), contents.len())
+    }
+
+    fn finish(mut self) -> String {
+        let assigned: Vec<(String, usize)> = self.variables.iter()
+            .filter(|(_, (_, assigned))| *assigned)
+            .map(|(name, (slot, _))| (name.clone(), *slot))
+            .collect();
+        for (name, slot) in assigned {
+            let label = self.string(&format!("{} = ", name));
+            self.emit("mov $1, %edi");
+            self.emit(&format!("lea {}(%rip), %rsi", label.0));
+            self.emit(&format!("mov ${}, %edx", label.1));
+            self.emit("call pc_write");
+            self.emit(&format!("mov pc_var{}(%rip), %rax", slot));
+            self.emit("mov $1, %edi");
+            self.emit("call pc_print_int");
+            self.emit("mov $1, %edi");
+            self.emit("lea pc_newline(%rip), %rsi");
+            self.emit("mov $1, %edx");
+            self.emit("call pc_write");
+        }
+
+        let mut out = String::new();
+        out.push_str("# Build with: as -o program.o program.s && ld -o program program.o\n");
+        out.push_str("    .text\n");
+        out.push_str("    .globl _start\n");
+        out.push_str("_start:\n");
+        for line in &self.body {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str("    mov $60, %eax\n");
+        out.push_str("    xor %edi, %edi\n");
+        out.push_str("    syscall\n");
+
+        out.push_str(RUNTIME);
+        if self.uses_overflow {
+            out.push_str("\npc_overflow:\n");
+            out.push_str("    lea pc_msg_overflow(%rip), %rsi\n");
+            out.push_str("    mov $32, %edx\n");
+            out.push_str("    jmp pc_fail\n");
+        }
+        if self.uses_div {
+            out.push_str("\npc_division_by_zero:\n");
+            out.push_str("    lea pc_msg_division(%rip), %rsi\n");
+            out.push_str("    mov $32, %edx\n");
+            out.push_str("    jmp pc_fail\n");
+        }
+        if self.uses_bool {
+            out.push_str("\n# Reports a value in %rax that does not fit a booleano variable; the\n");
+            out.push_str("# rest of the message is in %rsi/%rdx.\n");
+            out.push_str("pc_booleano_error:\n");
+            out.push_str("    push %rsi\n");
+            out.push_str("    push %rdx\n");
+            out.push_str("    push %rax\n");
+            out.push_str("    mov $2, %edi\n");
+            out.push_str("    lea pc_msg_mismatch(%rip), %rsi\n");
+            out.push_str("    mov $44, %edx\n");
+            out.push_str("    call pc_write\n");
+            out.push_str("    pop %rax\n");
+            out.push_str("    mov $2, %edi\n");
+            out.push_str("    call pc_print_int\n");
+            out.push_str("    pop %rdx\n");
+            out.push_str("    pop %rsi\n");
+            out.push_str("    jmp pc_fail\n");
+        }
+
+        out.push_str("\n    .section .rodata\n");
+        out.push_str("pc_newline:\n    .ascii \"\\n\"\n");
+        if self.uses_overflow {
+            out.push_str("pc_msg_overflow:\n    .ascii \"Runtime error: Integer overflow\\n\"\n");
+        }
+        if self.uses_div {
+            out.push_str("pc_msg_division:\n    .ascii \"Runtime error: Division by zero\\n\"\n");
+        }
+        if self.uses_bool {
+            out.push_str("pc_msg_mismatch:\n    .ascii \"Runtime error: Type mismatch: cannot assign \"\n");
+        }
+        for (index, string) in self.strings.iter().enumerate() {
+            out.push_str(&format!(".Lstr{}:\n    .ascii {:?}\n", index, string));
+        }
+
+        if !self.variables.is_empty() {
+            out.push_str("\n    .bss\n");
+            out.push_str("    .align 8\n");
+            let mut slots: Vec<_> = self.variables.iter().collect();
+            slots.sort_by_key(|(_, (slot, _))| *slot);
+            for (name, (slot, _)) in slots {
+                out.push_str(&format!("pc_var{}:    # {}\n    .zero 8\n", slot, name));
+            }
+        }
+
+        out.push_str("\n    .section .note.GNU-stack,\"\",@progbits\n");
+        out
+    }
+}
+
+/// Routines every generated program uses: writing a buffer and printing a
+/// signed integer in decimal.
+const RUNTIME: &str = "
+# Writes %rdx bytes at %rsi to the file descriptor in %edi.
+pc_write:
+    mov $1, %eax
+    syscall
+    ret
+
+# Writes %rax in decimal to the file descriptor in %edi.
+pc_print_int:
+    sub $32, %rsp
+    lea 32(%rsp), %rsi
+    mov %rax, %r8
+    test %rax, %rax
+    jns 1f
+    neg %rax
+1:
+    mov $10, %rcx
+2:
+    xor %edx, %edx
+    div %rcx
+    add $'0', %dl
+    dec %rsi
+    mov %dl, (%rsi)
+    test %rax, %rax
+    jnz 2b
+    test %r8, %r8
+    jns 3f
+    dec %rsi
+    movb $'-', (%rsi)
+3:
+    lea 32(%rsp), %rdx
+    sub %rsi, %rdx
+    call pc_write
+    add $32, %rsp
+    ret
+
+# Writes the %rdx bytes at %rsi to stderr and exits with status 1.
+pc_fail:
+    mov $2, %edi
+    call pc_write
+    mov $60, %eax
+    mov $1, %edi
+    syscall
+";
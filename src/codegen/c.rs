@@ -0,0 +1,288 @@
+use std::collections::BTreeSet;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use super::is_always_boolean;
+
+const INDENT: &str = "    ";
+
+/// C keywords, plus library functions and macros visible inside the
+/// generated `main`. Variables with these names are emitted with a `pc_`
+/// prefix, the same prefix the generated helpers use.
+const RESERVED: &[&str] = &[
+    "alignas", "alignof", "auto", "bool", "break", "case", "char", "const",
+    "constexpr", "continue", "default", "do", "double", "else", "enum",
+    "extern", "false", "float", "for", "goto", "if", "inline", "int", "long",
+    "nullptr", "register", "restrict", "return", "short", "signed", "sizeof",
+    "static", "static_assert", "struct", "switch", "thread_local", "true",
+    "typedef", "typeof", "union", "unsigned", "void", "volatile", "while",
+    "printf", "stderr", "stdin", "stdout", "BUFSIZ", "EOF", "EXIT_FAILURE",
+    "EXIT_SUCCESS", "FILENAME_MAX", "FOPEN_MAX", "NULL", "RAND_MAX",
+    "SEEK_CUR", "SEEK_END", "SEEK_SET", "TMP_MAX", "CHAR_BIT", "CHAR_MAX",
+    "CHAR_MIN", "INT_MAX", "INT_MIN", "LLONG_MAX", "LLONG_MIN", "LONG_MAX",
+    "LONG_MIN", "MB_LEN_MAX", "SCHAR_MAX", "SCHAR_MIN", "SHRT_MAX",
+    "SHRT_MIN", "UCHAR_MAX", "UINT_MAX", "ULLONG_MAX", "ULONG_MAX",
+    "USHRT_MAX",
+];
+
+/// Translates a program into a standalone C program.
+///
+/// Every variable is a `long long`. Arithmetic goes through small helpers that
+/// stop the program with the interpreter's `Division by zero` and
+/// `Integer overflow` errors, printed as `Runtime error: ...` on stderr, and
+/// values stored in `booleano` variables are checked the same way. Operands
+/// are evaluated left to right, so a program with several errors reports the
+/// same one as the interpreter. Functions can only be provided by a host, so
+/// every call stops the program with `Undefined function`. On success the
+/// program prints the variable table in the same sorted `name = value` format
+/// as `Interpreter::print_variables`, so both outputs can be diffed.
+pub fn generate(program: &Program) -> String {
+    let mut generator = CGenerator::default();
+    for statement in &program.statements {
+        generator.statement(statement);
+    }
+    generator.finish()
+}
+
+#[derive(Default)]
+struct CGenerator {
+    body: Vec<String>,
+    variables: BTreeSet<String>,
+    assigned: BTreeSet<String>,
+    booleans: BTreeSet<String>,
+    helpers: BTreeSet<Helper>,
+    /// Number of `pc_t` temporaries emitted so far.
+    temporaries: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Helper {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Booleano,
+    Call,
+}
+
+impl CGenerator {
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => self.assign(name, value),
+            Statement::Const { name, value } => self.assign(name, value),
+            Statement::Declare { name, type_annotation, value } => {
+                self.variables.insert(name.clone());
+                if *type_annotation == Some(Type::Boolean) {
+                    self.booleans.insert(name.clone());
+                }
+                if let Some(value) = value {
+                    self.assign(name, value);
+                }
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let value = Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(name.clone())),
+                    operator: operator.clone(),
+                    right: Box::new(value.clone()),
+                };
+                self.assign(name, &value);
+            }
+            Statement::Expr(expr) => {
+                let expr = self.expr(expr, false);
+                self.body.push(format!("(void)({});", expr));
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &Expr) {
+        let mut rendered = self.expr(value, false);
+        if self.booleans.contains(name) && !is_always_boolean(value) {
+            self.helpers.insert(Helper::Booleano);
+            rendered = format!("pc_booleano({:?}, {})", name, rendered);
+        }
+        self.variables.insert(name.to_string());
+        self.assigned.insert(name.to_string());
+        self.body.push(format!("{} = {};", c_name(name), rendered));
+    }
+
+    /// Renders an expression. `nested` is set for operands of a comparison,
+    /// where another comparison has to be parenthesized.
+    fn expr(&mut self, expr: &Expr, nested: bool) -> String {
+        match expr {
+            Expr::Number(i64::MIN) => "LLONG_MIN".to_string(),
+            Expr::Number(value) => format!("{}LL", value),
+            Expr::Identifier(name) => c_name(name),
+            Expr::BinaryOp { left, operator, right } => {
+                // C leaves the order of operands unspecified, so when both
+                // can fail the left one is computed first into a temporary,
+                // to report the same error as the interpreter.
+                let hoist = can_fail(left) && can_fail(right);
+                let (helper, symbol) = match operator {
+                    Operator::Plus => (Some(Helper::Add), "pc_add"),
+                    Operator::Minus => (Some(Helper::Sub), "pc_sub"),
+                    Operator::Multiply => (Some(Helper::Mul), "pc_mul"),
+                    Operator::Divide => (Some(Helper::Div), "pc_div"),
+                    Operator::Eq => (None, "=="),
+                    Operator::NotEq => (None, "!="),
+                    Operator::Gt => (None, ">"),
+                    Operator::Lt => (None, "<"),
+                    Operator::GtEq => (None, ">="),
+                    Operator::LtEq => (None, "<="),
+                };
+                match helper {
+                    Some(helper) => {
+                        self.helpers.insert(helper);
+                        let left = self.operand(left, false, hoist);
+                        let right = self.expr(right, false);
+                        format!("{}({}, {})", symbol, left, right)
+                    }
+                    None => {
+                        let left = self.operand(left, true, hoist);
+                        let right = self.expr(right, true);
+                        let rendered = format!("{} {} {}", left, symbol, right);
+                        if nested { format!("({})", rendered) } else { rendered }
+                    }
+                }
+            }
+            Expr::Call { name, args } => {
+                // Generated programs have no host, so every call fails once
+                // its arguments have been evaluated.
+                // Arguments that can fail are computed into temporaries,
+                // since those of later arguments are emitted before the call.
+                self.helpers.insert(Helper::Call);
+                let call = format!("pc_call({:?})", name);
+                if args.is_empty() {
+                    return call;
+                }
+                let mut parts: Vec<String> = args
+                    .iter()
+                    .map(|arg| {
+                        let arg = self.operand(arg, false, can_fail(arg));
+                        format!("(void)({})", arg)
+                    })
+                    .collect();
+                parts.push(call);
+                format!("({})", parts.join(", "))
+            }
+        }
+    }
+
+    /// Renders an operand, first storing it in a new temporary when `hoist`
+    /// is set.
+    fn operand(&mut self, expr: &Expr, nested: bool, hoist: bool) -> String {
+        if !hoist {
+            return self.expr(expr, nested);
+        }
+        let rendered = self.expr(expr, false);
+        let name = format!("pc_t{}", self.temporaries);
+        self.temporaries += 1;
+        self.body.push(format!("long long {} = {};", name, rendered));
+        name
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        out.push_str("#include <limits.h>\n");
+        out.push_str("#include <stdio.h>\n");
+        out.push_str("#include <stdlib.h>\n");
+
+        if !self.helpers.is_empty() {
+            out.push_str("\nstatic void pc_error(const char *message) {\n");
+            out.push_str("    fprintf(stderr, \"Runtime error: %s\\n\", message);\n");
+            out.push_str("    exit(1);\n");
+            out.push_str("}\n");
+        }
+        for helper in &self.helpers {
+            out.push('\n');
+            out.push_str(helper_source(*helper));
+        }
+
+        out.push_str("\nint main(void) {\n");
+        for name in &self.variables {
+            out.push_str(&format!("{}long long {} = 0;\n", INDENT, c_name(name)));
+        }
+        if !self.variables.is_empty() && !self.body.is_empty() {
+            out.push('\n');
+        }
+        for line in &self.body {
+            out.push_str(INDENT);
+            out.push_str(line);
+            out.push('\n');
+        }
+        if !self.assigned.is_empty() {
+            out.push('\n');
+        }
+        for name in &self.assigned {
+            out.push_str(&format!("{}printf(\"{} = %lld\\n\", {});\n", INDENT, name, c_name(name)));
+        }
+        out.push_str(INDENT);
+        out.push_str("return 0;\n");
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Whether evaluating an expression can stop the program.
+fn can_fail(expr: &Expr) -> bool {
+    match expr {
+        Expr::Number(_) | Expr::Identifier(_) => false,
+        Expr::BinaryOp { left, operator, right } => {
+            matches!(operator, Operator::Plus | Operator::Minus | Operator::Multiply | Operator::Divide)
+                || can_fail(left)
+                || can_fail(right)
+        }
+        Expr::Call { .. } => true,
+    }
+}
+
+fn helper_source(helper: Helper) -> &'static str {
+    match helper {
+        Helper::Add => "static long long pc_add(long long a, long long b) {
+    long long result;
+    if (__builtin_add_overflow(a, b, &result)) pc_error(\"Integer overflow\");
+    return result;
+}
+",
+        Helper::Sub => "static long long pc_sub(long long a, long long b) {
+    long long result;
+    if (__builtin_sub_overflow(a, b, &result)) pc_error(\"Integer overflow\");
+    return result;
+}
+",
+        Helper::Mul => "static long long pc_mul(long long a, long long b) {
+    long long result;
+    if (__builtin_mul_overflow(a, b, &result)) pc_error(\"Integer overflow\");
+    return result;
+}
+",
+        Helper::Div => "static long long pc_div(long long a, long long b) {
+    if (b == 0) pc_error(\"Division by zero\");
+    if (a == LLONG_MIN && b == -1) pc_error(\"Integer overflow\");
+    return a / b;
+}
+",
+        Helper::Booleano => "static long long pc_booleano(const char *name, long long value) {
+    if (value != 0 && value != 1) {
+        fprintf(stderr, \"Runtime error: Type mismatch: cannot assign %lld to %s of type booleano\\n\", value, name);
+        exit(1);
+    }
+    return value;
+}
+",
+        Helper::Call => "static long long pc_call(const char *name) {
+    fprintf(stderr, \"Runtime error: Undefined function: %s\\n\", name);
+    exit(1);
+}
+",
+    }
+}
+
+fn c_name(name: &str) -> String {
+    // Identifiers starting with `__` or `_` and an uppercase letter are
+    // reserved for the implementation.
+    let implementation = name.starts_with("__")
+        || (name.starts_with('_') && name[1..].starts_with(|c: char| c.is_ascii_uppercase()));
+    if RESERVED.contains(&name) || implementation || name.starts_with("pc_") {
+        format!("pc_{}", name)
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,36 @@
+use crate::ast::Program;
+use super::flowchart::{Flowchart, Shape};
+
+const INDENT: &str = "    ";
+
+/// Draws a program as a Graphviz DOT flowchart, for `dot -Tsvg`.
+pub fn generate(program: &Program) -> String {
+    render(&Flowchart::from_program(program))
+}
+
+/// Renders a flowchart as a DOT `digraph`, with nodes named `n0`, `n1`, ...
+/// in the order they were added.
+pub fn render(chart: &Flowchart) -> String {
+    let mut out = String::from("digraph program {\n");
+    out.push_str(&format!("{}node [fontname=\"Helvetica\"];\n", INDENT));
+    for (index, node) in chart.nodes.iter().enumerate() {
+        let shape = match node.shape {
+            Shape::Terminal => "shape=box, style=rounded",
+            Shape::Process => "shape=box",
+            Shape::Decision => "shape=diamond",
+        };
+        out.push_str(&format!("{}n{} [label={}, {}];\n", INDENT, index, quote(&node.label), shape));
+    }
+    for edge in &chart.edges {
+        match &edge.label {
+            Some(label) => out.push_str(&format!("{}n{} -> n{} [label={}];\n", INDENT, edge.from, edge.to, quote(label))),
+            None => out.push_str(&format!("{}n{} -> n{};\n", INDENT, edge.from, edge.to)),
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
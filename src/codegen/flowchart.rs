@@ -0,0 +1,64 @@
+use crate::ast::Program;
+use crate::format::format_statement;
+
+/// The outline of a flowchart node, following the usual conventions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// Rounded box for the start and end of the program.
+    Terminal,
+    /// Rectangle for a statement that runs and moves on.
+    Process,
+    /// Diamond for a condition with labelled outgoing edges.
+    Decision,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub shape: Shape,
+    pub label: String,
+}
+
+/// An arrow between two nodes, given as indices into `Flowchart::nodes`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    /// Shown on the branches of a decision, e.g. `yes` or `no`.
+    pub label: Option<String>,
+}
+
+/// A program as a flowchart, shared by the DOT and Mermaid backends.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Flowchart {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Flowchart {
+    /// Builds the chart of a program: a `Start` terminal, one process box
+    /// per statement labelled with its formatted source, and an `End`
+    /// terminal, joined in order. Statements have no branches yet, so
+    /// decisions only appear in charts built by hand.
+    pub fn from_program(program: &Program) -> Self {
+        let mut chart = Flowchart::default();
+        let mut previous = chart.add_node(Shape::Terminal, "Start");
+        for statement in &program.statements {
+            let node = chart.add_node(Shape::Process, &format_statement(statement));
+            chart.add_edge(previous, node, None);
+            previous = node;
+        }
+        let end = chart.add_node(Shape::Terminal, "End");
+        chart.add_edge(previous, end, None);
+        chart
+    }
+
+    /// Adds a node and returns its index.
+    pub fn add_node(&mut self, shape: Shape, label: &str) -> usize {
+        self.nodes.push(Node { shape, label: label.to_string() });
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, label: Option<&str>) {
+        self.edges.push(Edge { from, to, label: label.map(str::to_string) });
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeSet;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use super::is_always_boolean;
+
+const INDENT: &str = "    ";
+
+/// JavaScript reserved words, plus the names the generated module defines.
+/// Variables with these names are emitted with a `pc_` prefix, the same
+/// prefix the generated helpers use.
+const RESERVED: &[&str] = &[
+    "arguments", "await", "break", "case", "catch", "class", "const",
+    "continue", "debugger", "default", "delete", "do", "else", "enum", "eval",
+    "export", "extends", "false", "finally", "for", "function", "if",
+    "implements", "import", "in", "instanceof", "interface", "let", "new",
+    "null", "package", "private", "protected", "public", "return", "static",
+    "super", "switch", "this", "throw", "true", "try", "typeof", "undefined",
+    "var", "void", "while", "with", "yield", "BigInt", "PseudocuError", "run",
+];
+
+/// Translates a program into a self-contained JavaScript module.
+///
+/// Every value is a `BigInt`. Sums, differences and products are checked
+/// against the 64-bit range, and division truncates toward zero like the
+/// interpreter, so the module raises the same `Division by zero`,
+/// `Integer overflow` and `booleano` mismatch errors, and every call throws
+/// `Undefined function` since only a host can provide functions. The module
+/// exports a `run()` function returning the variables in the same sorted
+/// order as `Interpreter::print_variables`, and the `PseudocuError` class it
+/// throws.
+pub fn generate(program: &Program) -> String {
+    let mut generator = JsGenerator::default();
+    for statement in &program.statements {
+        generator.statement(statement);
+    }
+    generator.finish()
+}
+
+#[derive(Default)]
+struct JsGenerator {
+    body: Vec<String>,
+    variables: BTreeSet<String>,
+    assigned: BTreeSet<String>,
+    booleans: BTreeSet<String>,
+    uses_check: bool,
+    uses_div: bool,
+    uses_bool: bool,
+    uses_call: bool,
+}
+
+impl JsGenerator {
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => self.assign(name, value),
+            Statement::Const { name, value } => self.assign(name, value),
+            Statement::Declare { name, type_annotation, value } => {
+                self.variables.insert(name.clone());
+                if *type_annotation == Some(Type::Boolean) {
+                    self.booleans.insert(name.clone());
+                }
+                if let Some(value) = value {
+                    self.assign(name, value);
+                }
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let value = Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(name.clone())),
+                    operator: operator.clone(),
+                    right: Box::new(value.clone()),
+                };
+                self.assign(name, &value);
+            }
+            Statement::Expr(expr) => {
+                let expr = self.expr(expr);
+                self.body.push(format!("{};", expr));
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &Expr) {
+        let mut rendered = self.expr(value);
+        if self.booleans.contains(name) && !is_always_boolean(value) {
+            self.uses_bool = true;
+            rendered = format!("pc_booleano({:?}, {})", name, rendered);
+        }
+        self.variables.insert(name.to_string());
+        self.assigned.insert(name.to_string());
+        self.body.push(format!("{} = {};", js_name(name), rendered));
+    }
+
+    /// Renders an expression. Every compound expression is either a helper
+    /// call or parenthesized, so operands never need extra parentheses.
+    fn expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Number(value) => format!("{}n", value),
+            Expr::Identifier(name) => js_name(name),
+            Expr::BinaryOp { left, operator, right } => {
+                let left = self.expr(left);
+                let right = self.expr(right);
+                let symbol = match operator {
+                    Operator::Plus => "+",
+                    Operator::Minus => "-",
+                    Operator::Multiply => "*",
+                    Operator::Divide => {
+                        self.uses_div = true;
+                        self.uses_check = true;
+                        return format!("pc_div({}, {})", left, right);
+                    }
+                    Operator::Eq => "===",
+                    Operator::NotEq => "!==",
+                    Operator::Gt => ">",
+                    Operator::Lt => "<",
+                    Operator::GtEq => ">=",
+                    Operator::LtEq => "<=",
+                };
+                if matches!(operator, Operator::Plus | Operator::Minus | Operator::Multiply) {
+                    self.uses_check = true;
+                    format!("pc_check({} {} {})", left, symbol, right)
+                } else {
+                    format!("({} {} {} ? 1n : 0n)", left, symbol, right)
+                }
+            }
+            Expr::Call { name, args } => {
+                // Generated modules have no host, so every call fails once
+                // its arguments have been evaluated.
+                self.uses_call = true;
+                let mut rendered = vec![format!("{:?}", name)];
+                rendered.extend(args.iter().map(|arg| self.expr(arg)));
+                format!("pc_call({})", rendered.join(", "))
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        out.push_str("export class PseudocuError extends Error {}\n");
+
+        if self.uses_check {
+            out.push_str("\nconst PC_MIN = -(2n ** 63n);\n");
+            out.push_str("const PC_MAX = 2n ** 63n - 1n;\n\n");
+            out.push_str("function pc_check(value) {\n");
+            out.push_str("    if (value < PC_MIN || value > PC_MAX) {\n");
+            out.push_str("        throw new PseudocuError(\"Integer overflow\");\n");
+            out.push_str("    }\n");
+            out.push_str("    return value;\n");
+            out.push_str("}\n");
+        }
+        if self.uses_div {
+            out.push_str("\n// BigInt division already truncates toward zero.\n");
+            out.push_str("function pc_div(a, b) {\n");
+            out.push_str("    if (b === 0n) {\n");
+            out.push_str("        throw new PseudocuError(\"Division by zero\");\n");
+            out.push_str("    }\n");
+            out.push_str("    return pc_check(a / b);\n");
+            out.push_str("}\n");
+        }
+        if self.uses_bool {
+            out.push_str("\nfunction pc_booleano(name, value) {\n");
+            out.push_str("    if (value !== 0n && value !== 1n) {\n");
+            out.push_str("        throw new PseudocuError(\n");
+            out.push_str("            `Type mismatch: cannot assign ${value} to ${name} of type booleano`\n");
+            out.push_str("        );\n");
+            out.push_str("    }\n");
+            out.push_str("    return value;\n");
+            out.push_str("}\n");
+        }
+        if self.uses_call {
+            out.push_str("\nfunction pc_call(name) {\n");
+            out.push_str("    throw new PseudocuError(`Undefined function: ${name}`);\n");
+            out.push_str("}\n");
+        }
+
+        out.push_str("\nexport function run() {\n");
+        for name in &self.variables {
+            out.push_str(&format!("{}let {};\n", INDENT, js_name(name)));
+        }
+        if !self.variables.is_empty() && !self.body.is_empty() {
+            out.push('\n');
+        }
+        for line in &self.body {
+            out.push_str(INDENT);
+            out.push_str(line);
+            out.push('\n');
+        }
+        if !self.variables.is_empty() || !self.body.is_empty() {
+            out.push('\n');
+        }
+        if self.assigned.is_empty() {
+            out.push_str(INDENT);
+            out.push_str("return {};\n");
+        } else {
+            out.push_str(INDENT);
+            out.push_str("return {\n");
+            for name in &self.assigned {
+                out.push_str(&format!("{}{}{:?}: {},\n", INDENT, INDENT, name, js_name(name)));
+            }
+            out.push_str(INDENT);
+            out.push_str("};\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn js_name(name: &str) -> String {
+    if RESERVED.contains(&name) || name.starts_with("pc_") || name.starts_with("PC_") {
+        format!("pc_{}", name)
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,38 @@
+use crate::ast::Program;
+use super::flowchart::{Flowchart, Shape};
+
+const INDENT: &str = "    ";
+
+/// Draws a program as a Mermaid flowchart, which Markdown renderers such as
+/// GitHub's display inside a ```` ```mermaid ```` block.
+pub fn generate(program: &Program) -> String {
+    render(&Flowchart::from_program(program))
+}
+
+/// Renders a flowchart top to bottom, with nodes named `n0`, `n1`, ... in
+/// the order they were added.
+pub fn render(chart: &Flowchart) -> String {
+    let mut out = String::from("flowchart TD\n");
+    for (index, node) in chart.nodes.iter().enumerate() {
+        let label = quote(&node.label);
+        let node = match node.shape {
+            Shape::Terminal => format!("n{}([{}])", index, label),
+            Shape::Process => format!("n{}[{}]", index, label),
+            Shape::Decision => format!("n{}{{{}}}", index, label),
+        };
+        out.push_str(&format!("{}{}\n", INDENT, node));
+    }
+    for edge in &chart.edges {
+        match &edge.label {
+            Some(label) => out.push_str(&format!("{}n{} -->|{}| n{}\n", INDENT, edge.from, quote(label), edge.to)),
+            None => out.push_str(&format!("{}n{} --> n{}\n", INDENT, edge.from, edge.to)),
+        }
+    }
+    out
+}
+
+/// Quotes a label so operators like `>` are not read as Mermaid syntax.
+/// Quotes inside it are written as the `#quot;` entity.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;"))
+}
@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use super::is_always_boolean;
+
+const INDENT: &str = "    ";
+
+/// Names that cannot be used as Python variables inside the generated
+/// `main`, either because they are keywords or because the generated code
+/// calls them. They are emitted with a `_pc_` prefix, the same prefix the
+/// generated helpers use.
+const RESERVED: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break",
+    "class", "continue", "def", "del", "elif", "else", "except", "finally",
+    "for", "from", "global", "if", "import", "in", "int", "is", "lambda",
+    "nonlocal", "not", "or", "pass", "print", "raise", "return", "try",
+    "while", "with", "yield",
+];
+
+/// Translates a program into a runnable Python script.
+///
+/// The script keeps the interpreter's semantics where Python differs:
+/// division truncates toward zero instead of flooring, comparisons produce
+/// `1`/`0`, and division by zero or a value that does not fit a `booleano`
+/// variable stops the script with the same `Runtime error` message, as does
+/// any call, since only a host can provide functions. When it finishes, the
+/// script prints every variable in the same sorted `name = value` format as
+/// `Interpreter::print_variables`. Python integers never overflow, so
+/// results the interpreter rejects with `Integer overflow` are printed in
+/// full instead.
+pub fn generate(program: &Program) -> String {
+    let mut generator = PythonGenerator::default();
+    for statement in &program.statements {
+        generator.statement(statement);
+    }
+    generator.finish()
+}
+
+#[derive(Default)]
+struct PythonGenerator {
+    body: Vec<String>,
+    assigned: BTreeSet<String>,
+    booleans: BTreeSet<String>,
+    uses_div: bool,
+    uses_bool: bool,
+    uses_call: bool,
+}
+
+impl PythonGenerator {
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => {
+                let rendered = self.expr(value, 0);
+                self.assign(name, value, rendered);
+            }
+            Statement::Const { name, value } => {
+                let value = self.expr(value, 0);
+                self.assigned.insert(name.clone());
+                self.body.push(format!("{} = {}", python_name(name), value));
+            }
+            Statement::Declare { name, type_annotation, value } => {
+                let boolean = *type_annotation == Some(Type::Boolean);
+                let line = match value {
+                    Some(value) => {
+                        self.assigned.insert(name.clone());
+                        let rendered = self.expr(value, 0);
+                        let rendered = if boolean {
+                            self.check_bool(name, value, rendered)
+                        } else {
+                            rendered
+                        };
+                        format!("{}: int = {}", python_name(name), rendered)
+                    }
+                    None => format!("{}: int", python_name(name)),
+                };
+                self.body.push(line);
+                if boolean {
+                    self.booleans.insert(name.clone());
+                }
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let value = Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(name.clone())),
+                    operator: operator.clone(),
+                    right: Box::new(value.clone()),
+                };
+                let rendered = self.expr(&value, 0);
+                self.assign(name, &value, rendered);
+            }
+            Statement::Expr(expr) => {
+                let expr = self.expr(expr, 0);
+                self.body.push(expr);
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &Expr, rendered: String) {
+        let rendered = if self.booleans.contains(name) {
+            self.check_bool(name, value, rendered)
+        } else {
+            rendered
+        };
+        self.assigned.insert(name.to_string());
+        self.body.push(format!("{} = {}", python_name(name), rendered));
+    }
+
+    /// Wraps a value stored in a `booleano` variable in a runtime check,
+    /// unless the expression can only produce `0` or `1`.
+    fn check_bool(&mut self, name: &str, value: &Expr, rendered: String) -> String {
+        if is_always_boolean(value) {
+            return rendered;
+        }
+        self.uses_bool = true;
+        format!("_pc_booleano({:?}, {})", name, rendered)
+    }
+
+    fn expr(&mut self, expr: &Expr, parent: u8) -> String {
+        match expr {
+            Expr::Number(value) => value.to_string(),
+            Expr::Identifier(name) => python_name(name),
+            Expr::BinaryOp { left, operator, right } => self.binary(left, operator, right, parent),
+            Expr::Call { name, args } => {
+                // Generated scripts have no host, so every call fails once its
+                // arguments have been evaluated.
+                self.uses_call = true;
+                let mut rendered = vec![format!("{:?}", name)];
+                rendered.extend(args.iter().map(|arg| self.expr(arg, 0)));
+                format!("_pc_call({})", rendered.join(", "))
+            }
+        }
+    }
+
+    /// Renders a binary operation; `parent` is the precedence of the enclosing
+    /// Python operator, used to decide whether parentheses are needed.
+    fn binary(&mut self, left: &Expr, operator: &Operator, right: &Expr, parent: u8) -> String {
+        let (symbol, precedence) = match operator {
+            Operator::Plus => ("+", 1),
+            Operator::Minus => ("-", 1),
+            Operator::Multiply => ("*", 2),
+            Operator::Divide => {
+                self.uses_div = true;
+                let left = self.expr(left, 0);
+                let right = self.expr(right, 0);
+                return format!("_pc_div({}, {})", left, right);
+            }
+            Operator::Eq => return self.comparison(left, "==", right),
+            Operator::NotEq => return self.comparison(left, "!=", right),
+            Operator::Gt => return self.comparison(left, ">", right),
+            Operator::Lt => return self.comparison(left, "<", right),
+            Operator::GtEq => return self.comparison(left, ">=", right),
+            Operator::LtEq => return self.comparison(left, "<=", right),
+        };
+
+        let left = self.expr(left, precedence);
+        // The right operand binds tighter so `a - (b - c)` keeps its parentheses.
+        let right = self.expr(right, precedence + 1);
+        let rendered = format!("{} {} {}", left, symbol, right);
+        if precedence < parent {
+            format!("({})", rendered)
+        } else {
+            rendered
+        }
+    }
+
+    fn comparison(&mut self, left: &Expr, symbol: &str, right: &Expr) -> String {
+        let left = self.expr(left, 1);
+        let right = self.expr(right, 1);
+        format!("int({} {} {})", left, symbol, right)
+    }
+
+    fn finish(self) -> String {
+        let raises = self.uses_div || self.uses_bool || self.uses_call;
+        let mut out = String::new();
+
+        if raises {
+            out.push_str("import sys\n\n\n");
+            out.push_str("class PseudocuError(Exception):\n");
+            out.push_str("    pass\n\n\n");
+        }
+        if self.uses_div {
+            out.push_str("def _pc_div(a, b):\n");
+            out.push_str("    \"\"\"Integer division truncating toward zero.\"\"\"\n");
+            out.push_str("    if b == 0:\n");
+            out.push_str("        raise PseudocuError(\"Division by zero\")\n");
+            out.push_str("    q = abs(a) // abs(b)\n");
+            out.push_str("    return q if (a < 0) == (b < 0) else -q\n\n\n");
+        }
+        if self.uses_bool {
+            out.push_str("def _pc_booleano(name, value):\n");
+            out.push_str("    if value not in (0, 1):\n");
+            out.push_str("        raise PseudocuError(\n");
+            out.push_str("            f\"Type mismatch: cannot assign {value} to {name} of type booleano\"\n");
+            out.push_str("        )\n");
+            out.push_str("    return value\n\n\n");
+        }
+        if self.uses_call {
+            out.push_str("def _pc_call(name, *args):\n");
+            out.push_str("    raise PseudocuError(f\"Undefined function: {name}\")\n\n\n");
+        }
+
+        out.push_str("def main():\n");
+        for line in &self.body {
+            out.push_str(INDENT);
+            out.push_str(line);
+            out.push('\n');
+        }
+        for name in &self.assigned {
+            out.push_str(&format!("{}print(f\"{} = {{{}}}\")\n", INDENT, name, python_name(name)));
+        }
+        if self.body.is_empty() {
+            out.push_str(INDENT);
+            out.push_str("pass\n");
+        }
+
+        out.push_str("\n\nif __name__ == \"__main__\":\n");
+        if raises {
+            out.push_str("    try:\n");
+            out.push_str("        main()\n");
+            out.push_str("    except PseudocuError as error:\n");
+            out.push_str("        print(f\"Runtime error: {error}\", file=sys.stderr)\n");
+            out.push_str("        sys.exit(1)\n");
+        } else {
+            out.push_str("    main()\n");
+        }
+        out
+    }
+}
+
+fn python_name(name: &str) -> String {
+    if RESERVED.contains(&name) || name.starts_with("_pc_") {
+        format!("_pc_{}", name)
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use super::is_always_boolean;
+
+const INDENT: &str = "  ";
+
+/// Translates a program into a WebAssembly text module.
+///
+/// Each variable becomes a mutable `i64` global, and the exported `run`
+/// function executes the program and returns the final value of every
+/// assigned variable, in the same sorted order as
+/// `Interpreter::print_variables`. The module has no imports, so runtime
+/// errors are traps: division by zero traps in `i64.div_s` itself,
+/// overflowing arithmetic raises the `integer overflow` trap, and storing a
+/// value other than `0` or `1` in a `booleano` variable or calling a
+/// function, which only a host can provide, reaches `unreachable`.
+pub fn generate(program: &Program) -> String {
+    let mut generator = WatGenerator::default();
+    for statement in &program.statements {
+        generator.statement(statement);
+    }
+    generator.finish()
+}
+
+#[derive(Default)]
+struct WatGenerator {
+    body: Vec<String>,
+    variables: BTreeSet<String>,
+    assigned: BTreeSet<String>,
+    booleans: BTreeSet<String>,
+    uses_add: bool,
+    uses_sub: bool,
+    uses_mul: bool,
+    uses_bool: bool,
+}
+
+impl WatGenerator {
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => self.assign(name, value),
+            Statement::Const { name, value } => self.assign(name, value),
+            Statement::Declare { name, type_annotation, value } => {
+                self.variables.insert(name.clone());
+                if *type_annotation == Some(Type::Boolean) {
+                    self.booleans.insert(name.clone());
+                }
+                if let Some(value) = value {
+                    self.assign(name, value);
+                }
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let value = Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(name.clone())),
+                    operator: operator.clone(),
+                    right: Box::new(value.clone()),
+                };
+                self.assign(name, &value);
+            }
+            Statement::Expr(expr) => {
+                self.expr(expr);
+                self.emit("drop");
+            }
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &Expr) {
+        self.expr(value);
+        if self.booleans.contains(name) && !is_always_boolean(value) {
+            self.uses_bool = true;
+            self.emit("call $pc_booleano");
+        }
+        self.variables.insert(name.to_string());
+        self.assigned.insert(name.to_string());
+        self.emit(&format!("global.set ${}", name));
+    }
+
+    /// Emits instructions pushing the value of `expr` on the operand stack.
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(value) => self.emit(&format!("i64.const {}", value)),
+            Expr::Identifier(name) => {
+                self.variables.insert(name.clone());
+                self.emit(&format!("global.get ${}", name));
+            }
+            Expr::BinaryOp { left, operator, right } => {
+                self.expr(left);
+                self.expr(right);
+                let instruction = match operator {
+                    Operator::Plus => {
+                        self.uses_add = true;
+                        "call $pc_add"
+                    }
+                    Operator::Minus => {
+                        self.uses_sub = true;
+                        "call $pc_sub"
+                    }
+                    Operator::Multiply => {
+                        self.uses_mul = true;
+                        "call $pc_mul"
+                    }
+                    Operator::Divide => "i64.div_s",
+                    Operator::Eq => "i64.eq",
+                    Operator::NotEq => "i64.ne",
+                    Operator::Gt => "i64.gt_s",
+                    Operator::Lt => "i64.lt_s",
+                    Operator::GtEq => "i64.ge_s",
+                    Operator::LtEq => "i64.le_s",
+                };
+                self.emit(instruction);
+                if !matches!(
+                    operator,
+                    Operator::Plus | Operator::Minus | Operator::Multiply | Operator::Divide
+                ) {
+                    self.emit("i64.extend_i32_u");
+                }
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.expr(arg);
+                    self.emit("drop");
+                }
+                self.emit("unreachable");
+            }
+        }
+    }
+
+    fn emit(&mut self, instruction: &str) {
+        self.body.push(format!("{}{}{}", INDENT, INDENT, instruction));
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        out.push_str("(module\n");
+        for name in &self.variables {
+            out.push_str(&format!("{}(global ${} (mut i64) (i64.const 0))\n", INDENT, name));
+        }
+
+        if self.uses_add || self.uses_sub || self.uses_mul {
+            out.push_str(HELPER_OVERFLOW);
+        }
+        if self.uses_add {
+            out.push_str(HELPER_ADD);
+        }
+        if self.uses_sub {
+            out.push_str(HELPER_SUB);
+        }
+        if self.uses_mul {
+            out.push_str(HELPER_MUL);
+        }
+        if self.uses_bool {
+            out.push_str(HELPER_BOOLEANO);
+        }
+
+        out.push('\n');
+        if !self.assigned.is_empty() {
+            let names: Vec<_> = self.assigned.iter().map(String::as_str).collect();
+            out.push_str(&format!("{};; Returns {}.\n", INDENT, names.join(", ")));
+        }
+        out.push_str(&format!("{}(func (export \"run\")", INDENT));
+        if !self.assigned.is_empty() {
+            out.push_str(" (result");
+            for _ in &self.assigned {
+                out.push_str(" i64");
+            }
+            out.push(')');
+        }
+        out.push('\n');
+        for line in &self.body {
+            out.push_str(line);
+            out.push('\n');
+        }
+        for name in &self.assigned {
+            out.push_str(&format!("{}{}global.get ${}\n", INDENT, INDENT, name));
+        }
+        out.push_str(&format!("{})\n", INDENT));
+        out.push_str(")\n");
+        out
+    }
+}
+
+const HELPER_OVERFLOW: &str = "
+  ;; Raises the same `integer overflow` trap as `i64.div_s`.
+  (func $pc_overflow
+    (drop (i64.div_s (i64.const -9223372036854775808) (i64.const -1))))
+";
+
+const HELPER_ADD: &str = "
+  (func $pc_add (param $a i64) (param $b i64) (result i64)
+    (local $r i64)
+    (local.set $r (i64.add (local.get $a) (local.get $b)))
+    ;; Overflow when both operands have a sign different from the result.
+    (if (i64.lt_s
+          (i64.and
+            (i64.xor (local.get $a) (local.get $r))
+            (i64.xor (local.get $b) (local.get $r)))
+          (i64.const 0))
+      (then (call $pc_overflow)))
+    (local.get $r))
+";
+
+const HELPER_SUB: &str = "
+  (func $pc_sub (param $a i64) (param $b i64) (result i64)
+    (local $r i64)
+    (local.set $r (i64.sub (local.get $a) (local.get $b)))
+    ;; Overflow when the operands differ in sign and the result does not
+    ;; keep the sign of `a`.
+    (if (i64.lt_s
+          (i64.and
+            (i64.xor (local.get $a) (local.get $b))
+            (i64.xor (local.get $a) (local.get $r)))
+          (i64.const 0))
+      (then (call $pc_overflow)))
+    (local.get $r))
+";
+
+const HELPER_MUL: &str = "
+  (func $pc_mul (param $a i64) (param $b i64) (result i64)
+    (local $r i64)
+    (local.set $r (i64.mul (local.get $a) (local.get $b)))
+    ;; Dividing back recovers `b` unless the product wrapped; for
+    ;; `-1 * i64::MIN` the division itself traps.
+    (if (i64.ne (local.get $a) (i64.const 0))
+      (then
+        (if (i64.ne (i64.div_s (local.get $r) (local.get $a)) (local.get $b))
+          (then (call $pc_overflow)))))
+    (local.get $r))
+";
+
+const HELPER_BOOLEANO: &str = "
+  (func $pc_booleano (param $value i64) (result i64)
+    (if (i64.gt_u (local.get $value) (i64.const 1))
+      (then unreachable))
+    (local.get $value))
+";
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    /// Push `constants[index]`.
+    Constant(usize),
+    /// Push the value held in a slot.
+    Load(usize),
+    /// Pop a value into a slot, enforcing its declared type.
+    Store(usize),
+    /// Pop a value into a slot and mark it constant.
+    DefineConst(usize),
+    /// Mark a slot as declared, popping its initial value when `initialized`.
+    Declare {
+        slot: usize,
+        type_annotation: Option<Type>,
+        initialized: bool,
+    },
+    /// Fail if the slot is a constant or, in strict mode, undeclared.
+    CheckAssignable(usize),
+    /// Fail if the slot is a constant.
+    CheckNotConstant(usize),
+    /// Fail if the slot already holds a variable.
+    CheckUndefined(usize),
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Equal,
+    NotEqual,
+    Greater,
+    Less,
+    GreaterEqual,
+    LessEqual,
+    /// Pop `args` values and push the result of calling host function
+    /// `functions[function]` with them, first argument deepest.
+    Call {
+        function: usize,
+        args: usize,
+    },
+    Pop,
+}
+
+impl Instruction {
+    fn from_operator(operator: &Operator) -> Self {
+        match operator {
+            Operator::Plus => Instruction::Add,
+            Operator::Minus => Instruction::Subtract,
+            Operator::Multiply => Instruction::Multiply,
+            Operator::Divide => Instruction::Divide,
+            Operator::Eq => Instruction::Equal,
+            Operator::NotEq => Instruction::NotEqual,
+            Operator::Gt => Instruction::Greater,
+            Operator::Lt => Instruction::Less,
+            Operator::GtEq => Instruction::GreaterEqual,
+            Operator::LtEq => Instruction::LessEqual,
+        }
+    }
+}
+
+/// A compiled program: the instruction stream, its constant pool, the name
+/// of every variable slot and the name of every function it calls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub code: Vec<Instruction>,
+    pub constants: Vec<i64>,
+    pub names: Vec<String>,
+    pub functions: Vec<String>,
+}
+
+pub struct Compiler {
+    code: Vec<Instruction>,
+    constants: Vec<i64>,
+    constant_indices: HashMap<i64, usize>,
+    names: Vec<String>,
+    slots: HashMap<String, usize>,
+    functions: Vec<String>,
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compiler {
+    pub fn new() -> Self {
+        Compiler {
+            code: Vec::new(),
+            constants: Vec::new(),
+            constant_indices: HashMap::new(),
+            names: Vec::new(),
+            slots: HashMap::new(),
+            functions: Vec::new(),
+        }
+    }
+
+    pub fn compile(mut self, program: &Program) -> Chunk {
+        for statement in &program.statements {
+            self.compile_statement(statement);
+        }
+        Chunk {
+            code: self.code,
+            constants: self.constants,
+            names: self.names,
+            functions: self.functions,
+        }
+    }
+
+    fn compile_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => {
+                let slot = self.slot(name);
+                self.code.push(Instruction::CheckAssignable(slot));
+                self.compile_expr(value);
+                self.code.push(Instruction::Store(slot));
+            }
+            Statement::Const { name, value } => {
+                let slot = self.slot(name);
+                self.code.push(Instruction::CheckNotConstant(slot));
+                self.code.push(Instruction::CheckUndefined(slot));
+                self.compile_expr(value);
+                self.code.push(Instruction::DefineConst(slot));
+            }
+            Statement::Declare { name, type_annotation, value } => {
+                let slot = self.slot(name);
+                self.code.push(Instruction::CheckUndefined(slot));
+                if let Some(value) = value {
+                    self.compile_expr(value);
+                }
+                self.code.push(Instruction::Declare {
+                    slot,
+                    type_annotation: *type_annotation,
+                    initialized: value.is_some(),
+                });
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let slot = self.slot(name);
+                self.code.push(Instruction::CheckAssignable(slot));
+                self.code.push(Instruction::Load(slot));
+                self.compile_expr(value);
+                self.code.push(Instruction::from_operator(operator));
+                self.code.push(Instruction::Store(slot));
+            }
+            Statement::Expr(expr) => {
+                self.compile_expr(expr);
+                self.code.push(Instruction::Pop);
+            }
+        }
+    }
+
+    fn compile_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(value) => {
+                let index = self.constant(*value);
+                self.code.push(Instruction::Constant(index));
+            }
+            Expr::Identifier(name) => {
+                let slot = self.slot(name);
+                self.code.push(Instruction::Load(slot));
+            }
+            Expr::BinaryOp { left, operator, right } => {
+                self.compile_expr(left);
+                self.compile_expr(right);
+                self.code.push(Instruction::from_operator(operator));
+            }
+            Expr::Call { name, args } => {
+                for arg in args {
+                    self.compile_expr(arg);
+                }
+                let function = self.function(name);
+                self.code.push(Instruction::Call { function, args: args.len() });
+            }
+        }
+    }
+
+    fn function(&mut self, name: &str) -> usize {
+        match self.functions.iter().position(|function| function == name) {
+            Some(index) => index,
+            None => {
+                self.functions.push(name.to_string());
+                self.functions.len() - 1
+            }
+        }
+    }
+
+    fn constant(&mut self, value: i64) -> usize {
+        if let Some(&index) = self.constant_indices.get(&value) {
+            return index;
+        }
+        let index = self.constants.len();
+        self.constants.push(value);
+        self.constant_indices.insert(value, index);
+        index
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        if let Some(&slot) = self.slots.get(name) {
+            return slot;
+        }
+        let slot = self.names.len();
+        self.names.push(name.to_string());
+        self.slots.insert(name.to_string(), slot);
+        slot
+    }
+}
+
+pub fn compile(program: &Program) -> Chunk {
+    Compiler::new().compile(program)
+}
@@ -0,0 +1,438 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::ops::ControlFlow;
+use std::path::Path;
+use crate::ast::Program;
+use crate::debugger::parse_expression;
+use crate::interpreter::{ExecutionHook, Interpreter};
+use crate::json::Json;
+use crate::lexer::tokenize;
+use crate::lsp::{read_message, write_message};
+use crate::parser::Parser;
+
+/// Programs run on a single thread, reported to the client with this id.
+const THREAD_ID: i64 = 1;
+const FRAME_ID: i64 = 1;
+const VARIABLES_REFERENCE: i64 = 1;
+
+/// Runs a Debug Adapter Protocol session over a pair of streams until the
+/// client disconnects or closes its end.
+///
+/// The program starts once the client has sent both `launch` and
+/// `configurationDone`, and runs on the interpreter with the session as its
+/// execution hook: while the program is paused, requests are read and
+/// answered from inside the hook.
+pub fn run<R: BufRead, W: Write>(reader: R, writer: W) -> io::Result<()> {
+    let mut session = Session::new(reader, writer);
+    while !(session.launched.is_some() && session.configured) {
+        match session.next_request(None)? {
+            Some(Resume::Stop) | None => return Ok(()),
+            Some(_) => {}
+        }
+    }
+
+    let Some(program) = session.launched.take() else {
+        return Ok(());
+    };
+    let mut interpreter = Interpreter::new();
+    let result = interpreter.run_with_hook(&program, &mut session);
+    if let Some(error) = session.error.take() {
+        return Err(error);
+    }
+    if session.disconnected {
+        return Ok(());
+    }
+    session.finish(&interpreter, result)?;
+
+    // Answer whatever the client still asks until it disconnects.
+    while let Some(resume) = session.next_request(None)? {
+        if resume == Resume::Stop {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// Pause before every statement (`stepIn`, and `stopOnEntry`).
+    Step,
+    /// Pause at the next statement on another line (`next`).
+    Next,
+    /// Pause only at breakpoints (`continue`).
+    Continue,
+}
+
+/// What to do after answering a request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Resume {
+    Wait,
+    Run,
+    Stop,
+}
+
+/// The program position a paused session reports to the client.
+struct Paused<'a> {
+    interpreter: &'a Interpreter,
+    line: Option<usize>,
+}
+
+struct Session<R: BufRead, W: Write> {
+    reader: R,
+    writer: W,
+    seq: i64,
+    path: String,
+    /// The parsed program, from `launch` until it starts running.
+    launched: Option<Program>,
+    configured: bool,
+    no_debug: bool,
+    /// Requested breakpoint lines, keyed by source path.
+    breakpoints: HashMap<String, BTreeSet<usize>>,
+    /// Statement lines of the launched program, to verify breakpoints.
+    statement_lines: BTreeSet<usize>,
+    mode: Mode,
+    on_entry: bool,
+    last_line: Option<usize>,
+    disconnected: bool,
+    /// An I/O error hit inside the hook, returned once the run stops.
+    error: Option<io::Error>,
+}
+
+impl<R: BufRead, W: Write> Session<R, W> {
+    fn new(reader: R, writer: W) -> Self {
+        Session {
+            reader,
+            writer,
+            seq: 0,
+            path: String::new(),
+            launched: None,
+            configured: false,
+            no_debug: false,
+            breakpoints: HashMap::new(),
+            statement_lines: BTreeSet::new(),
+            mode: Mode::Continue,
+            on_entry: false,
+            last_line: None,
+            disconnected: false,
+            error: None,
+        }
+    }
+
+    /// Reads and answers one request; `None` once the client closes its end.
+    ///
+    /// A message that cannot be read has no `seq` or command to answer, so
+    /// it is reported as an `output` event and the session carries on.
+    fn next_request(&mut self, paused: Option<&Paused>) -> io::Result<Option<Resume>> {
+        let message = match read_message(&mut self.reader) {
+            Ok(Some(message)) => message,
+            Ok(None) => {
+                self.disconnected = true;
+                return Ok(None);
+            }
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                self.event("output", Json::object([
+                    ("category", Json::string("stderr")),
+                    ("output", Json::string(format!("Invalid message: {}\n", e))),
+                ]))?;
+                return Ok(Some(Resume::Wait));
+            }
+            Err(e) => return Err(e),
+        };
+        if message.get("type").and_then(Json::as_str) != Some("request") {
+            return Ok(Some(Resume::Wait));
+        }
+        let command = message.get("command").and_then(Json::as_str).unwrap_or("").to_string();
+        let arguments = message.get("arguments").cloned().unwrap_or(Json::Null);
+        let request_seq = message.get("seq").and_then(Json::as_i64).unwrap_or(0);
+
+        let (result, resume) = self.request(&command, &arguments, paused);
+        let response = match result {
+            Ok(body) => Json::object([
+                ("type", Json::string("response")),
+                ("request_seq", Json::Int(request_seq)),
+                ("success", Json::Bool(true)),
+                ("command", Json::string(command.as_str())),
+                ("body", body),
+            ]),
+            Err(error) => Json::object([
+                ("type", Json::string("response")),
+                ("request_seq", Json::Int(request_seq)),
+                ("success", Json::Bool(false)),
+                ("command", Json::string(command.as_str())),
+                ("message", Json::string(error)),
+            ]),
+        };
+        self.send(response)?;
+        if command == "initialize" {
+            self.event("initialized", Json::Object(Vec::new()))?;
+        }
+        if resume == Resume::Stop {
+            self.disconnected = true;
+        }
+        Ok(Some(resume))
+    }
+
+    fn request(&mut self, command: &str, arguments: &Json, paused: Option<&Paused>) -> (Result<Json, String>, Resume) {
+        let resume_with = |session: &mut Self, mode: Mode| {
+            session.mode = mode;
+            let resume = if paused.is_some() { Resume::Run } else { Resume::Wait };
+            (Ok(Json::object([("allThreadsContinued", Json::Bool(true))])), resume)
+        };
+
+        match command {
+            "initialize" => (Ok(capabilities()), Resume::Wait),
+            "launch" => (self.launch(arguments), Resume::Wait),
+            "configurationDone" => {
+                self.configured = true;
+                (Ok(Json::Null), Resume::Wait)
+            }
+            "setBreakpoints" => (Ok(self.set_breakpoints(arguments)), Resume::Wait),
+            "threads" => {
+                let thread = Json::object([("id", Json::Int(THREAD_ID)), ("name", Json::string("main"))]);
+                (Ok(Json::object([("threads", Json::Array(vec![thread]))])), Resume::Wait)
+            }
+            "stackTrace" => (Ok(self.stack_trace(paused)), Resume::Wait),
+            "scopes" => {
+                let scope = Json::object([
+                    ("name", Json::string("Variables")),
+                    ("presentationHint", Json::string("locals")),
+                    ("variablesReference", Json::Int(VARIABLES_REFERENCE)),
+                    ("expensive", Json::Bool(false)),
+                ]);
+                (Ok(Json::object([("scopes", Json::Array(vec![scope]))])), Resume::Wait)
+            }
+            "variables" => (Ok(variables(arguments, paused)), Resume::Wait),
+            "evaluate" => (evaluate(arguments, paused), Resume::Wait),
+            "continue" => resume_with(self, Mode::Continue),
+            "next" => resume_with(self, Mode::Next),
+            "stepIn" => resume_with(self, Mode::Step),
+            // There are no functions to step out of.
+            "stepOut" => resume_with(self, Mode::Continue),
+            // Requests are only read while the program is paused.
+            "pause" => (Ok(Json::Null), Resume::Wait),
+            "disconnect" | "terminate" => (Ok(Json::Null), Resume::Stop),
+            _ => (Err(format!("Unsupported request: {}", command)), Resume::Wait),
+        }
+    }
+
+    fn launch(&mut self, arguments: &Json) -> Result<Json, String> {
+        let path = arguments
+            .get("program")
+            .and_then(Json::as_str)
+            .ok_or("Missing program to launch")?;
+        let source = fs::read_to_string(path).map_err(|e| format!("Error reading file '{}': {}", path, e))?;
+        let tokens = tokenize(source).map_err(|e| format!("Lexer error: {:?}", e))?;
+        let program = Parser::new(tokens).parse().map_err(|e| format!("Parser error: {}", e))?;
+
+        self.path = path.to_string();
+        self.no_debug = arguments.get("noDebug").and_then(Json::as_bool).unwrap_or(false);
+        self.on_entry = arguments.get("stopOnEntry").and_then(Json::as_bool).unwrap_or(false);
+        self.mode = if self.on_entry { Mode::Step } else { Mode::Continue };
+        self.statement_lines = program.lines.iter().copied().collect();
+        self.launched = Some(program);
+        Ok(Json::Null)
+    }
+
+    fn set_breakpoints(&mut self, arguments: &Json) -> Json {
+        let path = arguments.pointer(&["source", "path"]).and_then(Json::as_str).unwrap_or("");
+        let requested: Vec<usize> = arguments
+            .get("breakpoints")
+            .and_then(Json::as_array)
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(|breakpoint| breakpoint.get("line").and_then(Json::as_i64))
+            .filter_map(|line| usize::try_from(line).ok())
+            .collect();
+
+        // Before `launch` the program is unknown, so every line is accepted.
+        let known = !self.path.is_empty() && same_file(path, &self.path);
+        let verified = |line: &usize| !known || self.statement_lines.contains(line);
+        let breakpoints = requested
+            .iter()
+            .map(|line| {
+                let mut breakpoint = vec![
+                    ("verified".to_string(), Json::Bool(verified(line))),
+                    ("line".to_string(), Json::Int(*line as i64)),
+                ];
+                if !verified(line) {
+                    breakpoint.push(("message".to_string(), Json::string("No statement on this line")));
+                }
+                Json::Object(breakpoint)
+            })
+            .collect();
+        let lines = requested.iter().copied().filter(verified).collect();
+        self.breakpoints.insert(path.to_string(), lines);
+        Json::object([("breakpoints", Json::Array(breakpoints))])
+    }
+
+    fn stack_trace(&self, paused: Option<&Paused>) -> Json {
+        let Some(paused) = paused else {
+            return Json::object([("stackFrames", Json::Array(Vec::new())), ("totalFrames", Json::Int(0))]);
+        };
+        let name = Path::new(&self.path)
+            .file_name()
+            .map_or(self.path.clone(), |name| name.to_string_lossy().into_owned());
+        let frame = Json::object([
+            ("id", Json::Int(FRAME_ID)),
+            ("name", Json::string("main")),
+            ("line", Json::Int(paused.line.unwrap_or(0) as i64)),
+            ("column", Json::Int(1)),
+            ("source", Json::object([
+                ("name", Json::string(name)),
+                ("path", Json::string(self.path.as_str())),
+            ])),
+        ]);
+        Json::object([("stackFrames", Json::Array(vec![frame])), ("totalFrames", Json::Int(1))])
+    }
+
+    fn should_pause(&self, line: Option<usize>) -> bool {
+        if self.no_debug {
+            return false;
+        }
+        let new_line = line != self.last_line || line.is_none();
+        match self.mode {
+            Mode::Step => true,
+            Mode::Next => new_line,
+            Mode::Continue => new_line && line.is_some_and(|line| self.is_breakpoint(line)),
+        }
+    }
+
+    fn is_breakpoint(&self, line: usize) -> bool {
+        self.breakpoints
+            .iter()
+            .any(|(path, lines)| same_file(path, &self.path) && lines.contains(&line))
+    }
+
+    /// Reports the stop to the client and answers requests until one resumes
+    /// the program.
+    fn pause(&mut self, interpreter: &Interpreter, line: Option<usize>) -> io::Result<ControlFlow<()>> {
+        let reason = if self.on_entry {
+            "entry"
+        } else if self.mode == Mode::Continue {
+            "breakpoint"
+        } else {
+            "step"
+        };
+        self.on_entry = false;
+        self.last_line = line;
+        self.event("stopped", Json::object([
+            ("reason", Json::string(reason)),
+            ("threadId", Json::Int(THREAD_ID)),
+            ("allThreadsStopped", Json::Bool(true)),
+        ]))?;
+
+        let paused = Paused { interpreter, line };
+        loop {
+            match self.next_request(Some(&paused))? {
+                Some(Resume::Wait) => {}
+                Some(Resume::Run) => return Ok(ControlFlow::Continue(())),
+                Some(Resume::Stop) | None => return Ok(ControlFlow::Break(())),
+            }
+        }
+    }
+
+    /// Reports the end of the program: its final variables or runtime error
+    /// as output, then `exited` and `terminated`.
+    fn finish(&mut self, interpreter: &Interpreter, result: Result<(), String>) -> io::Result<()> {
+        let (output, category, exit_code) = match result {
+            Ok(()) => {
+                let mut variables: Vec<_> = interpreter.variables().collect();
+                variables.sort_by(|a, b| a.0.cmp(b.0));
+                let output: String = variables
+                    .iter()
+                    .map(|(name, value)| format!("{} = {}\n", name, value))
+                    .collect();
+                (output, "stdout", 0)
+            }
+            Err(error) => (format!("Runtime error: {}\n", error), "stderr", 1),
+        };
+        if !output.is_empty() {
+            self.event("output", Json::object([
+                ("category", Json::string(category)),
+                ("output", Json::string(output)),
+            ]))?;
+        }
+        self.event("exited", Json::object([("exitCode", Json::Int(exit_code))]))?;
+        self.event("terminated", Json::Object(Vec::new()))
+    }
+
+    fn event(&mut self, event: &str, body: Json) -> io::Result<()> {
+        self.send(Json::object([
+            ("type", Json::string("event")),
+            ("event", Json::string(event)),
+            ("body", body),
+        ]))
+    }
+
+    /// Sends a message, numbering it with the next `seq`.
+    fn send(&mut self, message: Json) -> io::Result<()> {
+        self.seq += 1;
+        let Json::Object(mut members) = message else {
+            unreachable!("messages are objects");
+        };
+        members.insert(0, ("seq".to_string(), Json::Int(self.seq)));
+        write_message(&mut self.writer, &Json::Object(members))
+    }
+}
+
+impl<R: BufRead, W: Write> ExecutionHook for Session<R, W> {
+    fn before_statement(&mut self, interpreter: &Interpreter, program: &Program, index: usize) -> ControlFlow<()> {
+        let line = program.line(index);
+        if !self.should_pause(line) {
+            return ControlFlow::Continue(());
+        }
+        match self.pause(interpreter, line) {
+            Ok(flow) => flow,
+            Err(error) => {
+                self.error = Some(error);
+                ControlFlow::Break(())
+            }
+        }
+    }
+}
+
+fn capabilities() -> Json {
+    Json::object([
+        ("supportsConfigurationDoneRequest", Json::Bool(true)),
+        ("supportsEvaluateForHovers", Json::Bool(true)),
+        ("supportsTerminateRequest", Json::Bool(true)),
+    ])
+}
+
+fn variables(arguments: &Json, paused: Option<&Paused>) -> Json {
+    let reference = arguments.get("variablesReference").and_then(Json::as_i64);
+    let variables = match paused {
+        Some(paused) if reference == Some(VARIABLES_REFERENCE) => paused
+            .interpreter
+            .variables()
+            .map(|(name, value)| Json::object([
+                ("name", Json::string(name)),
+                ("value", Json::string(value.to_string())),
+                ("variablesReference", Json::Int(0)),
+            ]))
+            .collect(),
+        _ => Vec::new(),
+    };
+    Json::object([("variables", Json::Array(variables))])
+}
+
+fn evaluate(arguments: &Json, paused: Option<&Paused>) -> Result<Json, String> {
+    let paused = paused.ok_or("The program is not paused")?;
+    let text = arguments.get("expression").and_then(Json::as_str).unwrap_or("").trim();
+    let expr = parse_expression(text)?;
+    let value = paused.interpreter.evaluate_expr(&expr)?;
+    Ok(Json::object([
+        ("result", Json::string(value.to_string())),
+        ("variablesReference", Json::Int(0)),
+    ]))
+}
+
+/// Whether two paths name the same file, comparing them as given when either
+/// cannot be resolved.
+fn same_file(a: &str, b: &str) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::BTreeSet;
+use std::io::{BufRead, Write};
+use std::ops::ControlFlow;
+use crate::ast::{Expr, Program};
+use crate::interpreter::{ExecutionHook, Interpreter};
+use crate::lexer::tokenize;
+use crate::parser::Parser;
+
+const HELP: &str = "\
+Commands:
+  s, step            run the next statement
+  n, next            run until the next line
+  c, continue        run until a breakpoint or the end
+  b, break [LINE]    set a breakpoint, or list breakpoints
+  d, delete LINE     remove a breakpoint
+  p, print EXPR      evaluate an expression
+  w, watch EXPR      evaluate an expression at every pause
+  unwatch EXPR       stop watching an expression
+  v, vars            show every variable
+  l, list            show the source around the current line
+  q, quit            stop the program
+  h, help            show this help
+";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Step,
+    Next,
+    Continue,
+}
+
+/// Interactive step debugger, run as the `ExecutionHook` of an interpreter.
+///
+/// It pauses before the first statement and then whenever the mode or a
+/// breakpoint asks for it, reading commands from `input` and writing to
+/// `output`. Breakpoints are source lines. Expressions typed at the prompt
+/// are parsed with `Parser::parse_expression` and evaluated against the
+/// interpreter's current variables without changing them.
+pub struct Debugger<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    source: Vec<String>,
+    mode: Mode,
+    breakpoints: BTreeSet<usize>,
+    watches: Vec<(String, Expr)>,
+    /// Line of the last pause, so `next` and breakpoints stop once per line.
+    last_line: Option<usize>,
+    quit: bool,
+}
+
+impl<R: BufRead, W: Write> Debugger<R, W> {
+    pub fn new(source: &str, input: R, output: W) -> Self {
+        Debugger {
+            input,
+            output,
+            source: source.lines().map(str::to_string).collect(),
+            mode: Mode::Step,
+            breakpoints: BTreeSet::new(),
+            watches: Vec::new(),
+            last_line: None,
+            quit: false,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, line: usize) {
+        self.breakpoints.insert(line);
+    }
+
+    /// Whether the user stopped the program with `quit`.
+    pub fn quit_requested(&self) -> bool {
+        self.quit
+    }
+
+    fn should_pause(&self, line: Option<usize>) -> bool {
+        let new_line = line != self.last_line || line.is_none();
+        match self.mode {
+            Mode::Step => true,
+            Mode::Next => new_line,
+            Mode::Continue => new_line && line.is_some_and(|line| self.breakpoints.contains(&line)),
+        }
+    }
+
+    /// Shows where execution stopped and reads commands until one resumes it.
+    fn pause(&mut self, interpreter: &Interpreter, program: &Program, index: usize) -> ControlFlow<()> {
+        let line = program.line(index);
+        self.last_line = line;
+        match line {
+            Some(line) => {
+                let text = self.source.get(line - 1).map_or("", |text| text.trim());
+                self.say(&format!("Paused at line {}: {}", line, text));
+            }
+            None => self.say(&format!("Paused at statement {}", index + 1)),
+        }
+        self.show_watches(interpreter);
+
+        loop {
+            let _ = write!(self.output, "(pcdb) ");
+            let _ = self.output.flush();
+            let mut command = String::new();
+            match self.input.read_line(&mut command) {
+                // Without more input, let the program finish.
+                Ok(0) | Err(_) => {
+                    self.say("");
+                    self.mode = Mode::Continue;
+                    self.breakpoints.clear();
+                    return ControlFlow::Continue(());
+                }
+                Ok(_) => {}
+            }
+            let command = command.trim();
+            let (name, argument) = match command.split_once(char::is_whitespace) {
+                Some((name, argument)) => (name, argument.trim()),
+                None => (command, ""),
+            };
+
+            match name {
+                "" => {}
+                "s" | "step" => {
+                    self.mode = Mode::Step;
+                    return ControlFlow::Continue(());
+                }
+                "n" | "next" => {
+                    self.mode = Mode::Next;
+                    return ControlFlow::Continue(());
+                }
+                "c" | "continue" => {
+                    self.mode = Mode::Continue;
+                    return ControlFlow::Continue(());
+                }
+                "q" | "quit" => {
+                    self.quit = true;
+                    return ControlFlow::Break(());
+                }
+                "b" | "break" if argument.is_empty() => {
+                    if self.breakpoints.is_empty() {
+                        self.say("No breakpoints");
+                    }
+                    let lines: Vec<String> = self.breakpoints.iter().map(|line| format!("Breakpoint at line {}", line)).collect();
+                    for line in lines {
+                        self.say(&line);
+                    }
+                }
+                "b" | "break" => match argument.parse::<usize>() {
+                    Ok(line) if program.lines.contains(&line) => {
+                        self.breakpoints.insert(line);
+                        self.say(&format!("Breakpoint at line {}", line));
+                    }
+                    Ok(line) => self.say(&format!("No statement at line {}", line)),
+                    Err(_) => self.say(&format!("Invalid line number: {}", argument)),
+                },
+                "d" | "delete" => match argument.parse::<usize>() {
+                    Ok(line) if self.breakpoints.remove(&line) => {
+                        self.say(&format!("Deleted breakpoint at line {}", line));
+                    }
+                    Ok(line) => self.say(&format!("No breakpoint at line {}", line)),
+                    Err(_) => self.say(&format!("Invalid line number: {}", argument)),
+                },
+                "p" | "print" => match parse_expression(argument) {
+                    Ok(expr) => {
+                        let value = describe(interpreter.evaluate_expr(&expr));
+                        self.say(&format!("{} = {}", argument, value));
+                    }
+                    Err(error) => self.say(&format!("Error: {}", error)),
+                },
+                "w" | "watch" => match parse_expression(argument) {
+                    Ok(expr) => {
+                        let value = describe(interpreter.evaluate_expr(&expr));
+                        self.say(&format!("Watching {} = {}", argument, value));
+                        self.watches.push((argument.to_string(), expr));
+                    }
+                    Err(error) => self.say(&format!("Error: {}", error)),
+                },
+                "unwatch" => {
+                    let before = self.watches.len();
+                    self.watches.retain(|(text, _)| text != argument);
+                    if self.watches.len() == before {
+                        self.say(&format!("Not watching {}", argument));
+                    }
+                }
+                "v" | "vars" => {
+                    let mut variables: Vec<_> = interpreter.variables().collect();
+                    variables.sort();
+                    if variables.is_empty() {
+                        self.say("No variables");
+                    }
+                    for (name, value) in variables {
+                        self.say(&format!("{} = {}", name, value));
+                    }
+                }
+                "l" | "list" => self.list(line),
+                "h" | "help" => {
+                    let _ = write!(self.output, "{}", HELP);
+                }
+                _ => self.say(&format!("Unknown command: {} (type 'help' for a list)", name)),
+            }
+        }
+    }
+
+    fn show_watches(&mut self, interpreter: &Interpreter) {
+        let lines: Vec<String> = self.watches
+            .iter()
+            .map(|(text, expr)| format!("  {} = {}", text, describe(interpreter.evaluate_expr(expr))))
+            .collect();
+        for line in lines {
+            self.say(&line);
+        }
+    }
+
+    /// Prints the current line with two lines of context on each side.
+    fn list(&mut self, current: Option<usize>) {
+        let current = current.unwrap_or(1);
+        let first = current.saturating_sub(2).max(1);
+        let last = (current + 2).min(self.source.len());
+        for number in first..=last {
+            let marker = if number == current { "->" } else { "  " };
+            let text = format!("{} {:>3} {}", marker, number, self.source[number - 1]);
+            self.say(text.trim_end());
+        }
+    }
+
+    fn say(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+}
+
+impl<R: BufRead, W: Write> ExecutionHook for Debugger<R, W> {
+    fn before_statement(&mut self, interpreter: &Interpreter, program: &Program, index: usize) -> ControlFlow<()> {
+        if self.should_pause(program.line(index)) {
+            self.pause(interpreter, program, index)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Parses a whole expression typed at the prompt.
+pub(crate) fn parse_expression(text: &str) -> Result<Expr, String> {
+    if text.is_empty() {
+        return Err("Expected an expression".to_string());
+    }
+    let tokens = tokenize(text.to_string()).map_err(|e| format!("Lexer error: {:?}", e))?;
+    let mut parser = Parser::new(tokens);
+    let expr = parser.parse_expression()?;
+    if !parser.is_at_end() {
+        return Err(format!("Unexpected input after expression: {}", text));
+    }
+    Ok(expr)
+}
+
+fn describe(result: Result<i64, String>) -> String {
+    match result {
+        Ok(value) => value.to_string(),
+        Err(error) => format!("<{}>", error),
+    }
+}
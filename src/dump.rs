@@ -0,0 +1,167 @@
+use crate::ast::{Expr, Operator, Program, Statement};
+use crate::json::Json;
+use crate::lexer::Token;
+
+const INDENT: &str = "  ";
+
+/// Version of the `--dump-ast=json` schema, bumped on incompatible changes.
+pub const AST_SCHEMA_VERSION: i64 = 1;
+
+/// Renders a program as an indented tree for `--dump-ast`, one node per
+/// line with its children indented below it.
+pub fn ast_tree(program: &Program) -> String {
+    let mut out = String::from("Program\n");
+    for (index, statement) in program.statements.iter().enumerate() {
+        let line = program.line(index).map_or(String::new(), |line| format!(" (line {})", line));
+        let (head, children): (String, Vec<&Expr>) = match statement {
+            Statement::Assign { name, value } => (format!("Assign {}", name), vec![value]),
+            Statement::Const { name, value } => (format!("Const {}", name), vec![value]),
+            Statement::Declare { name, type_annotation, value } => {
+                let head = match type_annotation {
+                    Some(ty) => format!("Declare {}: {}", name, ty),
+                    None => format!("Declare {}", name),
+                };
+                (head, value.iter().collect())
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                (format!("CompoundAssign {} {:?}", name, operator), vec![value])
+            }
+            Statement::Expr(expr) => ("Expr".to_string(), vec![expr]),
+        };
+        out.push_str(&format!("{}{}{}\n", INDENT, head, line));
+        for expr in children {
+            expr_tree(expr, 2, &mut out);
+        }
+    }
+    out
+}
+
+fn expr_tree(expr: &Expr, depth: usize, out: &mut String) {
+    let indent = INDENT.repeat(depth);
+    match expr {
+        Expr::Number(value) => out.push_str(&format!("{}Number {}\n", indent, value)),
+        Expr::Identifier(name) => out.push_str(&format!("{}Identifier {}\n", indent, name)),
+        Expr::BinaryOp { left, operator, right } => {
+            out.push_str(&format!("{}BinaryOp {:?}\n", indent, operator));
+            expr_tree(left, depth + 1, out);
+            expr_tree(right, depth + 1, out);
+        }
+        Expr::Call { name, args } => {
+            out.push_str(&format!("{}Call {}\n", indent, name));
+            for arg in args {
+                expr_tree(arg, depth + 1, out);
+            }
+        }
+    }
+}
+
+/// Converts a program to the `--dump-ast=json` schema:
+///
+/// - the program is `{"version", "statements"}`;
+/// - every statement and expression is an object whose `"kind"` names its
+///   variant, with the variant's fields as members. Statements also carry
+///   their source `"line"`, or `null` when the program has none;
+/// - `Statement::Expr` keeps its expression in `"expr"`, a missing type or
+///   value of a declaration is `null`, and types are `"entero"` or
+///   `"booleano"`;
+/// - operators are their variant names, such as `"Plus"` or `"GtEq"`.
+pub fn ast_json(program: &Program) -> Json {
+    let statements = program.statements
+        .iter()
+        .enumerate()
+        .map(|(index, statement)| statement_json(statement, program.line(index)))
+        .collect();
+    Json::object([
+        ("version", Json::Int(AST_SCHEMA_VERSION)),
+        ("statements", Json::Array(statements)),
+    ])
+}
+
+fn statement_json(statement: &Statement, line: Option<usize>) -> Json {
+    let line = line.map_or(Json::Null, |line| Json::Int(line as i64));
+    match statement {
+        Statement::Assign { name, value } => Json::object([
+            ("kind", Json::string("Assign")),
+            ("line", line),
+            ("name", Json::string(name.as_str())),
+            ("value", expr_json(value)),
+        ]),
+        Statement::Const { name, value } => Json::object([
+            ("kind", Json::string("Const")),
+            ("line", line),
+            ("name", Json::string(name.as_str())),
+            ("value", expr_json(value)),
+        ]),
+        Statement::Declare { name, type_annotation, value } => Json::object([
+            ("kind", Json::string("Declare")),
+            ("line", line),
+            ("name", Json::string(name.as_str())),
+            ("type", type_annotation.map_or(Json::Null, |ty| Json::string(ty.to_string()))),
+            ("value", value.as_ref().map_or(Json::Null, expr_json)),
+        ]),
+        Statement::CompoundAssign { name, operator, value } => Json::object([
+            ("kind", Json::string("CompoundAssign")),
+            ("line", line),
+            ("name", Json::string(name.as_str())),
+            ("operator", operator_json(operator)),
+            ("value", expr_json(value)),
+        ]),
+        Statement::Expr(expr) => Json::object([
+            ("kind", Json::string("Expr")),
+            ("line", line),
+            ("expr", expr_json(expr)),
+        ]),
+    }
+}
+
+fn expr_json(expr: &Expr) -> Json {
+    match expr {
+        Expr::Number(value) => Json::object([
+            ("kind", Json::string("Number")),
+            ("value", Json::Int(*value)),
+        ]),
+        Expr::Identifier(name) => Json::object([
+            ("kind", Json::string("Identifier")),
+            ("name", Json::string(name.as_str())),
+        ]),
+        Expr::BinaryOp { left, operator, right } => Json::object([
+            ("kind", Json::string("BinaryOp")),
+            ("operator", operator_json(operator)),
+            ("left", expr_json(left)),
+            ("right", expr_json(right)),
+        ]),
+        Expr::Call { name, args } => Json::object([
+            ("kind", Json::string("Call")),
+            ("name", Json::string(name.as_str())),
+            ("args", Json::Array(args.iter().map(expr_json).collect())),
+        ]),
+    }
+}
+
+fn operator_json(operator: &Operator) -> Json {
+    let name = match operator {
+        Operator::Plus => "Plus",
+        Operator::Minus => "Minus",
+        Operator::Multiply => "Multiply",
+        Operator::Divide => "Divide",
+        Operator::Eq => "Eq",
+        Operator::NotEq => "NotEq",
+        Operator::Gt => "Gt",
+        Operator::Lt => "Lt",
+        Operator::GtEq => "GtEq",
+        Operator::LtEq => "LtEq",
+    };
+    Json::string(name)
+}
+
+/// Lists tokens for `--dump-tokens`, one per line: the source line, the
+/// token type and the literal text it was read from.
+pub fn tokens(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            let token_type = format!("{:?}", token.token_type);
+            format!("{:>4}  {:<16} {:?}\n", token.line, token_type, token.literal)
+        })
+        .collect()
+}
@@ -0,0 +1,175 @@
+use crate::ast::{Expr, Operator, Program, Statement};
+use crate::lexer::{tokenize_with_comments, Comment};
+use crate::parser::Parser;
+
+/// Formats source code, keeping its comments.
+///
+/// Every statement goes on its own line with one space around operators and
+/// only the parentheses the parser's precedence levels require. Comments stay
+/// next to the statement they were written beside, and runs of blank lines
+/// are collapsed to one.
+pub fn format_source(source: &str) -> Result<String, String> {
+    let (tokens, comments) = tokenize_with_comments(source.to_string())
+        .map_err(|e| format!("Lexer error: {:?}", e))?;
+    // The lexer ends the stream at a character it does not know, so the
+    // parser stops there too; formatting would drop the rest of the file.
+    let unexpected = tokens.last().filter(|token| !token.literal.is_empty()).cloned();
+    let mut parser = Parser::new(tokens);
+    let program = parser.parse()?;
+    if !parser.is_at_end()
+        && let Some(token) = unexpected
+    {
+        return Err(format!("Unexpected character: {} at line {}", token.literal, token.line));
+    }
+
+    let source_lines: Vec<&str> = source.lines().collect();
+    let mut formatter = Formatter { out: String::new(), source_lines };
+    let mut comments = comments.into_iter().peekable();
+
+    for (index, statement) in program.statements.iter().enumerate() {
+        let line = program.line(index).unwrap_or(0);
+        let next_line = program.line(index + 1).unwrap_or(usize::MAX);
+
+        while let Some(comment) = comments.next_if(|comment| comment.line < line) {
+            formatter.comment(&comment);
+        }
+
+        formatter.blank_line_before(line);
+        formatter.out.push_str(&format_statement(statement));
+        if let Some(comment) = comments.next_if(|comment| comment.trailing && comment.line < next_line) {
+            formatter.out.push_str("  #");
+            formatter.out.push_str(&comment.text);
+        }
+        formatter.out.push('\n');
+    }
+    for comment in comments {
+        formatter.comment(&comment);
+    }
+
+    Ok(formatter.out)
+}
+
+struct Formatter<'a> {
+    out: String,
+    source_lines: Vec<&'a str>,
+}
+
+impl Formatter<'_> {
+    fn comment(&mut self, comment: &Comment) {
+        self.blank_line_before(comment.line);
+        self.out.push('#');
+        self.out.push_str(&comment.text);
+        self.out.push('\n');
+    }
+
+    /// Keeps a blank line in front of something that had one in the source.
+    fn blank_line_before(&mut self, line: usize) {
+        let blank = line >= 2
+            && self.source_lines.get(line - 2).is_some_and(|text| text.trim().is_empty());
+        if blank && !self.out.is_empty() {
+            self.out.push('\n');
+        }
+    }
+}
+
+/// Formats a program without comments, one statement per line.
+pub fn format_program(program: &Program) -> String {
+    program.statements
+        .iter()
+        .map(|statement| format_statement(statement) + "\n")
+        .collect()
+}
+
+pub fn format_statement(statement: &Statement) -> String {
+    match statement {
+        Statement::Assign { name, value } => format!("{} = {}", name, format_expr(value)),
+        Statement::Const { name, value } => format!("const {} = {}", name, format_expr(value)),
+        Statement::Declare { name, type_annotation, value } => {
+            let mut text = format!("var {}", name);
+            if let Some(ty) = type_annotation {
+                text.push_str(&format!(": {}", ty));
+            }
+            if let Some(value) = value {
+                text.push_str(&format!(" = {}", format_expr(value)));
+            }
+            text
+        }
+        Statement::CompoundAssign { name, operator, value } => {
+            format!("{} {}= {}", name, operator_symbol(operator), format_expr(value))
+        }
+        // A statement starting with an identifier is read as an assignment or
+        // a lone identifier, so an operation on one needs parentheses.
+        Statement::Expr(expr @ Expr::BinaryOp { .. }) if starts_with_identifier(expr) => {
+            format!("({})", format_expr(expr))
+        }
+        Statement::Expr(expr) => format_expr(expr),
+    }
+}
+
+/// Formats an expression with the minimal parentheses needed to parse back
+/// into the same tree.
+///
+/// The grammar has no negative literals, so a negative `Number`, which only
+/// the optimizer produces, is written as a subtraction from zero.
+pub fn format_expr(expr: &Expr) -> String {
+    expr_with_precedence(expr, 0)
+}
+
+fn expr_with_precedence(expr: &Expr, parent: u8) -> String {
+    match expr {
+        Expr::Number(i64::MIN) => format!("(0 - {} - 1)", i64::MAX),
+        Expr::Number(value) if *value < 0 => format!("(0 - {})", value.unsigned_abs()),
+        Expr::Number(value) => value.to_string(),
+        Expr::Identifier(name) => name.clone(),
+        Expr::Call { name, args } => {
+            let args: Vec<String> = args.iter().map(format_expr).collect();
+            format!("{}({})", name, args.join(", "))
+        }
+        Expr::BinaryOp { left, operator, right } => {
+            let precedence = precedence(operator);
+            let left = expr_with_precedence(left, precedence);
+            // Operators are left-associative, so an equal-precedence right
+            // operand keeps its parentheses.
+            let right = expr_with_precedence(right, precedence + 1);
+            let text = format!("{} {} {}", left, operator_symbol(operator), right);
+            if precedence < parent {
+                format!("({})", text)
+            } else {
+                text
+            }
+        }
+    }
+}
+
+fn precedence(operator: &Operator) -> u8 {
+    match operator {
+        Operator::Eq | Operator::NotEq | Operator::Gt | Operator::Lt
+        | Operator::GtEq | Operator::LtEq => 1,
+        Operator::Plus | Operator::Minus => 2,
+        Operator::Multiply | Operator::Divide => 3,
+    }
+}
+
+fn operator_symbol(operator: &Operator) -> &'static str {
+    match operator {
+        Operator::Plus => "+",
+        Operator::Minus => "-",
+        Operator::Multiply => "*",
+        Operator::Divide => "/",
+        Operator::Eq => "==",
+        Operator::NotEq => "!=",
+        Operator::Gt => ">",
+        Operator::Lt => "<",
+        Operator::GtEq => ">=",
+        Operator::LtEq => "<=",
+    }
+}
+
+fn starts_with_identifier(expr: &Expr) -> bool {
+    match expr {
+        Expr::Identifier(_) => true,
+        Expr::BinaryOp { left, .. } => starts_with_identifier(left),
+        // The parser reads a statement starting with a call as an expression.
+        Expr::Number(_) | Expr::Call { .. } => false,
+    }
+}
@@ -1,80 +1,690 @@
 use std::collections::HashMap;
-use crate::ast::{Expr, Operator, Program, Statement};
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use crate::output::{write_table, Output};
+
+#[derive(Default)]
+struct Variable {
+    value: Option<i64>,
+    defined: bool,
+    constant: bool,
+    declared: bool,
+    declared_type: Option<Type>,
+    /// Set by `set_variable` until the program assigns the variable, so a
+    /// `var` declaration of a host input does not count as a redefinition.
+    seeded: bool,
+}
+
+/// `Expr` with every identifier replaced by the slot of its variable.
+enum SlotExpr {
+    Number(i64),
+    Variable(usize),
+    BinaryOp {
+        left: Box<SlotExpr>,
+        operator: Operator,
+        right: Box<SlotExpr>,
+    },
+    /// A call, with the index of the host function, or `None` if no function
+    /// with that name was registered.
+    Call {
+        name: String,
+        function: Option<usize>,
+        args: Vec<SlotExpr>,
+    },
+}
+
+/// `Statement` with every variable name replaced by its slot.
+enum SlotStatement {
+    Assign {
+        slot: usize,
+        value: SlotExpr,
+    },
+    Const {
+        slot: usize,
+        value: SlotExpr,
+    },
+    Declare {
+        slot: usize,
+        type_annotation: Option<Type>,
+        value: Option<SlotExpr>,
+    },
+    CompoundAssign {
+        slot: usize,
+        operator: Operator,
+        value: SlotExpr,
+    },
+    Expr(SlotExpr),
+}
+
+/// A program whose variable names have been resolved to the slots of one
+/// interpreter by `Interpreter::prepare`, so it can be run any number of times
+/// without resolving it again.
+pub struct ResolvedProgram<'a> {
+    program: &'a Program,
+    statements: Vec<SlotStatement>,
+    /// The interpreter's name table when the program was resolved.
+    names: Vec<String>,
+    /// Name of the host function at each index when the program was
+    /// resolved, since calls refer to functions by index.
+    functions: Vec<String>,
+}
+
+impl ResolvedProgram<'_> {
+    /// The program that was resolved.
+    pub fn program(&self) -> &Program {
+        self.program
+    }
+}
+
+/// Callbacks around each statement run by `Interpreter::run_with_hook`, used
+/// by tools such as the debugger to observe or pause execution.
+pub trait ExecutionHook {
+    /// Called before the statement at `index` runs. Returning
+    /// `ControlFlow::Break` stops the program without an error.
+    fn before_statement(&mut self, interpreter: &Interpreter, program: &Program, index: usize) -> ControlFlow<()>;
+
+    /// Called after the statement at `index` ran successfully.
+    fn after_statement(&mut self, _interpreter: &Interpreter, _program: &Program, _index: usize) {}
+}
+
+/// Bounds on a run, for executing programs that cannot be trusted to finish.
+/// `None` leaves a resource unlimited, which is the default.
+///
+/// Exceeding a limit stops the program with an error starting with the name
+/// of the limit, e.g. `Step limit exceeded: more than 1000 statements`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    /// Statements executed by one call to `run`.
+    pub max_steps: Option<u64>,
+    /// How deeply an expression may nest; a number or variable on its own
+    /// has nesting 1. This limits the shape of the source rather than the
+    /// work done, so `run` checks the whole program before executing it.
+    pub max_nesting: Option<usize>,
+    /// Variables that are defined at the same time.
+    pub max_variables: Option<usize>,
+    /// Wall-clock time for one call to `run`, checked before each statement.
+    pub timeout: Option<Duration>,
+}
+
+impl Limits {
+    /// Checks every expression in a program against `max_nesting`.
+    ///
+    /// `run` does this before executing any statement. Optimizing a program
+    /// folds away nesting, so check the source before running an optimized
+    /// copy to reject the same programs either way.
+    pub fn check_nesting(&self, program: &Program) -> Result<(), String> {
+        if self.max_nesting.is_none() {
+            return Ok(());
+        }
+        for statement in &program.statements {
+            let value = match statement {
+                Statement::Assign { value, .. }
+                | Statement::Const { value, .. }
+                | Statement::CompoundAssign { value, .. }
+                | Statement::Expr(value) => Some(value),
+                Statement::Declare { value, .. } => value.as_ref(),
+            };
+            if let Some(value) = value {
+                self.check_depth(nesting(value))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_depth(&self, depth: usize) -> Result<(), String> {
+        match self.max_nesting {
+            Some(max) if depth > max => {
+                Err(format!("Nesting limit exceeded: expression nested more than {} levels", max))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A Rust function that programs can call, registered with
+/// `Interpreter::register_function`. It receives the evaluated arguments and
+/// returns the value of the call; an error stops the program like any other
+/// runtime error. Functions are `Send + Sync` so the interpreter that holds
+/// them can be moved to another thread.
+pub type HostFunction = Box<dyn Fn(&[i64]) -> Result<i64, String> + Send + Sync>;
+
+struct NoHook;
+
+impl ExecutionHook for NoHook {
+    fn before_statement(&mut self, _: &Interpreter, _: &Program, _: usize) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
 
 pub struct Interpreter {
-    variables: HashMap<String, i64>,
+    /// Variable storage, indexed by slot.
+    variables: Vec<Variable>,
+    /// Name of the variable held in each slot.
+    names: Vec<String>,
+    /// Slot assigned to each variable name.
+    slots: HashMap<String, usize>,
+    strict: bool,
+    limits: Limits,
+    /// Number of variables with `defined` set, checked against the limit.
+    defined: usize,
+    functions: Vec<HostFunction>,
+    /// Index in `functions` of each registered name.
+    function_slots: HashMap<String, usize>,
+    output: Output,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Interpreter {
     pub fn new() -> Self {
         Interpreter {
-            variables: HashMap::new(),
+            variables: Vec::new(),
+            names: Vec::new(),
+            slots: HashMap::new(),
+            strict: false,
+            limits: Limits::default(),
+            defined: 0,
+            functions: Vec::new(),
+            function_slots: HashMap::new(),
+            output: Box::new(io::stdout()),
         }
     }
 
-    pub fn run(&mut self, program: Program) -> Result<(), String> {
-        for statement in program.statements {
+    /// In strict mode every variable must be declared with `var` (or `const`)
+    /// before it is assigned.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Sends program output, such as the table from `print_variables`, to
+    /// `output` instead of stdout. Use an `output::SharedBuffer` to read it
+    /// back.
+    pub fn set_output<W: Write + Send + 'static>(&mut self, output: W) {
+        self.output = Box::new(output);
+    }
+
+    /// Makes a Rust function callable from programs as `name(...)`,
+    /// replacing any function already registered under that name. The
+    /// function checks its own number of arguments.
+    pub fn register_function<F>(&mut self, name: &str, function: F)
+    where
+        F: Fn(&[i64]) -> Result<i64, String> + Send + Sync + 'static,
+    {
+        match self.function_slots.get(name) {
+            Some(&index) => self.functions[index] = Box::new(function),
+            None => {
+                self.function_slots.insert(name.to_string(), self.functions.len());
+                self.functions.push(Box::new(function));
+            }
+        }
+    }
+
+    /// Sets a variable as if the program had assigned it, so a host can
+    /// provide inputs before `run`. Fails for constants, for values that do
+    /// not fit a declared type, and beyond the variable limit.
+    ///
+    /// A program may still declare an input with `var`: a declaration
+    /// without a value keeps the one set here, and one with a value replaces
+    /// it. Once the program has assigned the variable, it is an ordinary
+    /// variable again.
+    pub fn set_variable(&mut self, name: &str, value: i64) -> Result<(), String> {
+        let slot = self.slot(name);
+        self.check_not_constant(slot)?;
+        let seeded = !self.variables[slot].defined || self.variables[slot].seeded;
+        self.assign(slot, value)?;
+        // Strict mode only rejects assignments to variables the program never
+        // saw declared; a host-provided variable counts as declared.
+        self.variables[slot].declared = true;
+        self.variables[slot].seeded = seeded;
+        Ok(())
+    }
+
+    /// Executes a program. The program is only borrowed, so the same parsed
+    /// `Program` can be run again or inspected afterwards.
+    ///
+    /// Names are resolved to slots before the first statement runs, so
+    /// executing statements never hashes a variable name. To run the same
+    /// program repeatedly, `prepare` it once and use `run_prepared`.
+    pub fn run(&mut self, program: &Program) -> Result<(), String> {
+        self.run_with_hook(program, &mut NoHook)
+    }
+
+    /// Executes a program, calling `hook` around every statement.
+    pub fn run_with_hook<H: ExecutionHook>(&mut self, program: &Program, hook: &mut H) -> Result<(), String> {
+        let statements: Vec<SlotStatement> = program.statements
+            .iter()
+            .map(|statement| self.resolve_statement(statement))
+            .collect();
+        self.execute(program, &statements, hook)
+    }
+
+    /// Resolves every variable name of a program to a slot of this
+    /// interpreter, for `run_prepared`.
+    pub fn prepare<'a>(&mut self, program: &'a Program) -> ResolvedProgram<'a> {
+        let statements = program.statements
+            .iter()
+            .map(|statement| self.resolve_statement(statement))
+            .collect();
+        let mut functions = vec![String::new(); self.functions.len()];
+        for (name, &index) in &self.function_slots {
+            functions[index] = name.clone();
+        }
+        ResolvedProgram {
+            program,
+            statements,
+            names: self.names.clone(),
+            functions,
+        }
+    }
+
+    /// Executes a program resolved by `prepare`. It can also run on a fresh
+    /// interpreter, which takes over the name table, but not on one whose
+    /// slots have since been given to other names, nor on one that did not
+    /// register the same functions in the same order.
+    pub fn run_prepared(&mut self, resolved: &ResolvedProgram) -> Result<(), String> {
+        self.run_prepared_with_hook(resolved, &mut NoHook)
+    }
+
+    /// Like `run_prepared`, calling `hook` around every statement.
+    pub fn run_prepared_with_hook<H: ExecutionHook>(
+        &mut self,
+        resolved: &ResolvedProgram,
+        hook: &mut H,
+    ) -> Result<(), String> {
+        self.adopt_names(&resolved.names)?;
+        self.check_functions(&resolved.functions)?;
+        self.execute(resolved.program, &resolved.statements, hook)
+    }
+
+    fn execute<H: ExecutionHook>(
+        &mut self,
+        program: &Program,
+        statements: &[SlotStatement],
+        hook: &mut H,
+    ) -> Result<(), String> {
+        self.limits.check_nesting(program)?;
+        let started = Instant::now();
+        for (index, statement) in statements.iter().enumerate() {
+            self.check_step(index as u64, started)?;
+            if hook.before_statement(self, program, index).is_break() {
+                break;
+            }
             self.execute_statement(statement)?;
+            hook.after_statement(self, program, index);
+        }
+        Ok(())
+    }
+
+    /// Makes sure slot `i` holds `names[i]`, adding the slots this
+    /// interpreter does not have yet.
+    fn adopt_names(&mut self, names: &[String]) -> Result<(), String> {
+        for (slot, name) in names.iter().enumerate() {
+            match self.names.get(slot) {
+                Some(existing) if existing == name => {}
+                Some(_) => return Err("Program was prepared by an interpreter with other variables".to_string()),
+                None => {
+                    self.slot(name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes sure function `i` is still `functions[i]`, so resolved calls
+    /// reach the function they were resolved to.
+    fn check_functions(&self, functions: &[String]) -> Result<(), String> {
+        for (index, name) in functions.iter().enumerate() {
+            if self.function_slots.get(name) != Some(&index) {
+                return Err("Program was prepared by an interpreter with other functions".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the step and time limits before the statement that would be
+    /// step number `executed + 1` of this run.
+    fn check_step(&self, executed: u64, started: Instant) -> Result<(), String> {
+        if let Some(max) = self.limits.max_steps
+            && executed >= max
+        {
+            return Err(format!("Step limit exceeded: more than {} statements", max));
+        }
+        if let Some(timeout) = self.limits.timeout
+            && started.elapsed() > timeout
+        {
+            return Err(format!("Time limit exceeded: ran longer than {:?}", timeout));
         }
         Ok(())
     }
 
-    fn execute_statement(&mut self, statement: Statement) -> Result<(), String> {
+    fn check_depth(&self, depth: usize) -> Result<(), String> {
+        self.limits.check_depth(depth)
+    }
+
+    /// Marks a slot as defined, unless that would exceed the variable limit.
+    fn define(&mut self, slot: usize) -> Result<(), String> {
+        if self.variables[slot].defined {
+            return Ok(());
+        }
+        if let Some(max) = self.limits.max_variables
+            && self.defined >= max
+        {
+            return Err(format!("Variable limit exceeded: more than {} variables", max));
+        }
+        self.variables[slot].defined = true;
+        self.defined += 1;
+        Ok(())
+    }
+
+    fn slot(&mut self, name: &str) -> usize {
+        if let Some(&slot) = self.slots.get(name) {
+            return slot;
+        }
+        let slot = self.names.len();
+        self.names.push(name.to_string());
+        self.slots.insert(name.to_string(), slot);
+        self.variables.push(Variable::default());
+        slot
+    }
+
+    fn resolve_statement(&mut self, statement: &Statement) -> SlotStatement {
+        match statement {
+            Statement::Assign { name, value } => SlotStatement::Assign {
+                slot: self.slot(name),
+                value: self.resolve_expr(value),
+            },
+            Statement::Const { name, value } => SlotStatement::Const {
+                slot: self.slot(name),
+                value: self.resolve_expr(value),
+            },
+            Statement::Declare { name, type_annotation, value } => SlotStatement::Declare {
+                slot: self.slot(name),
+                type_annotation: *type_annotation,
+                value: value.as_ref().map(|value| self.resolve_expr(value)),
+            },
+            Statement::CompoundAssign { name, operator, value } => SlotStatement::CompoundAssign {
+                slot: self.slot(name),
+                operator: operator.clone(),
+                value: self.resolve_expr(value),
+            },
+            Statement::Expr(expr) => SlotStatement::Expr(self.resolve_expr(expr)),
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) -> SlotExpr {
+        match expr {
+            Expr::Number(val) => SlotExpr::Number(*val),
+            Expr::Identifier(name) => SlotExpr::Variable(self.slot(name)),
+            Expr::BinaryOp { left, operator, right } => SlotExpr::BinaryOp {
+                left: Box::new(self.resolve_expr(left)),
+                operator: operator.clone(),
+                right: Box::new(self.resolve_expr(right)),
+            },
+            Expr::Call { name, args } => SlotExpr::Call {
+                name: name.clone(),
+                function: self.function_slots.get(name).copied(),
+                args: args.iter().map(|arg| self.resolve_expr(arg)).collect(),
+            },
+        }
+    }
+
+    fn execute_statement(&mut self, statement: &SlotStatement) -> Result<(), String> {
         match statement {
-            Statement::Assign { name, value } => {
-                let val = self.evaluate_expr(value)?;
-                self.variables.insert(name, val);
+            SlotStatement::Assign { slot, value } => {
+                self.check_assignable(*slot)?;
+                let val = self.evaluate_slot_expr(value)?;
+                self.assign(*slot, val)?;
+            }
+            SlotStatement::Const { slot, value } => {
+                self.check_not_constant(*slot)?;
+                self.check_undefined(*slot)?;
+                let val = self.evaluate_slot_expr(value)?;
+                self.define(*slot)?;
+                self.variables[*slot] = Variable {
+                    value: Some(val),
+                    defined: true,
+                    constant: true,
+                    declared: true,
+                    declared_type: None,
+                    seeded: false,
+                };
+            }
+            SlotStatement::Declare { slot, type_annotation, value } => {
+                let seeded = self.variables[*slot].seeded;
+                if !seeded {
+                    self.check_undefined(*slot)?;
+                }
+                let val = match value {
+                    Some(expr) => Some(self.evaluate_slot_expr(expr)?),
+                    None if seeded => self.variables[*slot].value,
+                    None => None,
+                };
+                if let (Some(ty), Some(val)) = (*type_annotation, val) {
+                    check_type(&self.names[*slot], ty, val)?;
+                }
+                self.define(*slot)?;
+                self.variables[*slot] = Variable {
+                    value: val,
+                    defined: true,
+                    constant: false,
+                    declared: true,
+                    declared_type: *type_annotation,
+                    seeded: false,
+                };
+            }
+            SlotStatement::CompoundAssign { slot, operator, value } => {
+                self.check_assignable(*slot)?;
+                let current = self.read_slot(*slot)?;
+                let val = self.evaluate_slot_expr(value)?;
+                let result = apply_operator(operator, current, val)?;
+                self.assign(*slot, result)?;
             }
-            Statement::Expr(expr) => {
-                let _ = self.evaluate_expr(expr)?;
+            SlotStatement::Expr(expr) => {
+                let _ = self.evaluate_slot_expr(expr)?;
             }
         }
         Ok(())
     }
 
-    fn evaluate_expr(&self, expr: Expr) -> Result<i64, String> {
+    fn assign(&mut self, slot: usize, value: i64) -> Result<(), String> {
+        if let Some(ty) = self.variables[slot].declared_type {
+            check_type(&self.names[slot], ty, value)?;
+        }
+        self.define(slot)?;
+        self.variables[slot].value = Some(value);
+        self.variables[slot].seeded = false;
+        Ok(())
+    }
+
+    fn read_slot(&self, slot: usize) -> Result<i64, String> {
+        let variable = &self.variables[slot];
+        match variable.value {
+            Some(value) => Ok(value),
+            None if variable.defined => Err(format!("Uninitialized variable: {}", self.names[slot])),
+            None => Err(format!("Undefined variable: {}", self.names[slot])),
+        }
+    }
+
+    fn evaluate_slot_expr(&self, expr: &SlotExpr) -> Result<i64, String> {
         match expr {
-            Expr::Number(val) => Ok(val),
-            Expr::Identifier(name) => {
-                self.variables.get(&name)
-                    .copied()
-                    .ok_or_else(|| format!("Undefined variable: {}", name))
+            SlotExpr::Number(val) => Ok(*val),
+            SlotExpr::Variable(slot) => self.read_slot(*slot),
+            SlotExpr::BinaryOp { left, operator, right } => {
+                let left_val = self.evaluate_slot_expr(left)?;
+                let right_val = self.evaluate_slot_expr(right)?;
+                apply_operator(operator, left_val, right_val)
             }
+            SlotExpr::Call { name, function, args } => {
+                let values = args
+                    .iter()
+                    .map(|arg| self.evaluate_slot_expr(arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                // Functions registered after `prepare` are looked up by name.
+                match function.or_else(|| self.function_slots.get(name).copied()) {
+                    Some(index) => self.functions[index](&values),
+                    None => Err(format!("Undefined function: {}", name)),
+                }
+            }
+        }
+    }
+
+    /// Evaluates an expression against the current variables without
+    /// modifying them. Names are looked up through the name table.
+    pub fn evaluate_expr(&self, expr: &Expr) -> Result<i64, String> {
+        self.evaluate_expr_at(expr, 1)
+    }
+
+    fn evaluate_expr_at(&self, expr: &Expr, depth: usize) -> Result<i64, String> {
+        self.check_depth(depth)?;
+        match expr {
+            Expr::Number(val) => Ok(*val),
+            Expr::Identifier(name) => match self.slots.get(name) {
+                Some(&slot) => self.read_slot(slot),
+                None => Err(format!("Undefined variable: {}", name)),
+            },
             Expr::BinaryOp { left, operator, right } => {
-                let left_val = self.evaluate_expr(*left)?;
-                let right_val = self.evaluate_expr(*right)?;
-                
-                match operator {
-                    Operator::Plus => Ok(left_val + right_val),
-                    Operator::Minus => Ok(left_val - right_val),
-                    Operator::Multiply => Ok(left_val * right_val),
-                    Operator::Divide => {
-                        if right_val == 0 {
-                            Err("Division by zero".to_string())
-                        } else {
-                            Ok(left_val / right_val)
-                        }
-                    }
-                    Operator::Eq => Ok(if left_val == right_val { 1 } else { 0 }),
-                    Operator::NotEq => Ok(if left_val != right_val { 1 } else { 0 }),
-                    Operator::Gt => Ok(if left_val > right_val { 1 } else { 0 }),
-                    Operator::Lt => Ok(if left_val < right_val { 1 } else { 0 }),
-                    Operator::GtEq => Ok(if left_val >= right_val { 1 } else { 0 }),
-                    Operator::LtEq => Ok(if left_val <= right_val { 1 } else { 0 }),
+                let left_val = self.evaluate_expr_at(left, depth + 1)?;
+                let right_val = self.evaluate_expr_at(right, depth + 1)?;
+                apply_operator(operator, left_val, right_val)
+            }
+            Expr::Call { name, args } => {
+                let values = args
+                    .iter()
+                    .map(|arg| self.evaluate_expr_at(arg, depth + 1))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match self.function_slots.get(name) {
+                    Some(&index) => self.functions[index](&values),
+                    None => Err(format!("Undefined function: {}", name)),
                 }
             }
         }
     }
 
+    fn check_not_constant(&self, slot: usize) -> Result<(), String> {
+        if self.variables[slot].constant {
+            Err(format!("Cannot assign to constant: {}", self.names[slot]))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_undefined(&self, slot: usize) -> Result<(), String> {
+        if self.variables[slot].defined {
+            Err(format!("Variable already defined: {}", self.names[slot]))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_assignable(&self, slot: usize) -> Result<(), String> {
+        self.check_not_constant(slot)?;
+        if self.strict && !self.variables[slot].declared {
+            return Err(format!("Undeclared variable: {}", self.names[slot]));
+        }
+        Ok(())
+    }
+
+    fn variable(&self, name: &str) -> Option<&Variable> {
+        self.slots.get(name).map(|&slot| &self.variables[slot])
+    }
+
     pub fn get_variable(&self, name: &str) -> Option<i64> {
-        self.variables.get(name).copied()
+        self.variable(name).and_then(|variable| variable.value)
+    }
+
+    pub fn is_constant(&self, name: &str) -> bool {
+        self.variable(name).is_some_and(|variable| variable.constant)
     }
 
-    pub fn print_variables(&self) {
-        let mut vars: Vec<_> = self.variables.iter().collect();
-        vars.sort_by(|a, b| a.0.cmp(b.0));
-        for (name, value) in vars {
-            println!("{} = {}", name, value);
+    /// Every variable that holds a value, in the order the names were first
+    /// seen.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, i64)> {
+        with_values(&self.names, &self.variables)
+    }
+
+    /// Writes `name = value` for every variable, sorted by name, e.g. into a
+    /// buffer instead of stdout.
+    pub fn write_variables<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write_table(out, self.variables())
+    }
+
+    /// The table `print_variables` writes, as a string.
+    pub fn variables_table(&self) -> String {
+        let mut out = Vec::new();
+        self.write_variables(&mut out).expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("the table is valid UTF-8")
+    }
+
+    /// Writes the variable table to the output, stdout unless changed with
+    /// `set_output`.
+    pub fn print_variables(&mut self) -> io::Result<()> {
+        // Borrows the fields separately, since the output is written to.
+        write_table(&mut self.output, with_values(&self.names, &self.variables))?;
+        self.output.flush()
+    }
+}
+
+fn with_values<'a>(names: &'a [String], variables: &'a [Variable]) -> impl Iterator<Item = (&'a str, i64)> {
+    names.iter()
+        .zip(variables)
+        .filter_map(|(name, variable)| variable.value.map(|value| (name.as_str(), value)))
+}
+
+/// How deeply an expression nests, counting the expression itself.
+fn nesting(expr: &Expr) -> usize {
+    match expr {
+        Expr::Number(_) | Expr::Identifier(_) => 1,
+        Expr::BinaryOp { left, right, .. } => 1 + nesting(left).max(nesting(right)),
+        Expr::Call { args, .. } => 1 + args.iter().map(nesting).max().unwrap_or(0),
+    }
+}
+
+/// Applies a binary operator with the language's integer semantics. Shared by
+/// every backend that evaluates expressions so they agree on edge cases.
+pub fn apply_operator(operator: &Operator, left_val: i64, right_val: i64) -> Result<i64, String> {
+    let overflow = || "Integer overflow".to_string();
+    match operator {
+        Operator::Plus => left_val.checked_add(right_val).ok_or_else(overflow),
+        Operator::Minus => left_val.checked_sub(right_val).ok_or_else(overflow),
+        Operator::Multiply => left_val.checked_mul(right_val).ok_or_else(overflow),
+        Operator::Divide => {
+            if right_val == 0 {
+                Err("Division by zero".to_string())
+            } else {
+                left_val.checked_div(right_val).ok_or_else(overflow)
+            }
         }
+        Operator::Eq => Ok(if left_val == right_val { 1 } else { 0 }),
+        Operator::NotEq => Ok(if left_val != right_val { 1 } else { 0 }),
+        Operator::Gt => Ok(if left_val > right_val { 1 } else { 0 }),
+        Operator::Lt => Ok(if left_val < right_val { 1 } else { 0 }),
+        Operator::GtEq => Ok(if left_val >= right_val { 1 } else { 0 }),
+        Operator::LtEq => Ok(if left_val <= right_val { 1 } else { 0 }),
+    }
+}
+
+/// Checks that a value fits the type a variable was declared with.
+pub(crate) fn check_type(name: &str, ty: Type, value: i64) -> Result<(), String> {
+    match ty {
+        Type::Integer => Ok(()),
+        Type::Boolean if value == 0 || value == 1 => Ok(()),
+        Type::Boolean => Err(format!(
+            "Type mismatch: cannot assign {} to {} of type {}",
+            value, name, ty
+        )),
     }
 }
@@ -0,0 +1,313 @@
+use std::fmt;
+
+/// A JSON value, used by the tools that talk to editors and by
+/// `--dump-ast=json`.
+///
+/// Object members keep their insertion order so output is deterministic.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Builds an object from `(key, value)` pairs.
+    pub fn object<const N: usize>(members: [(&str, Json); N]) -> Json {
+        Json::Object(members.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    pub fn string(value: impl Into<String>) -> Json {
+        Json::String(value.into())
+    }
+
+    /// Looks up a member of an object; `None` for missing keys and non-objects.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Follows a path of object keys.
+    pub fn pointer(&self, path: &[&str]) -> Option<&Json> {
+        path.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Json::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Json, String> {
+        let mut parser = JsonParser { input: input.as_bytes(), position: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position < parser.input.len() {
+            return Err(format!("Unexpected trailing characters at offset {}", parser.position));
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Int(value) => write!(f, "{}", value),
+            Json::Float(value) if value.is_finite() => write!(f, "{}", value),
+            Json::Float(_) => write!(f, "null"),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for ch in value.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => write!(f, "{}", ch)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct JsonParser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => self.array(),
+            Some(b'{') => self.object(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(ch) => Err(format!("Unexpected character '{}' at offset {}", ch as char, self.position)),
+            None => Err("Unexpected end of input".to_string()),
+        }
+    }
+
+    fn literal(&mut self, text: &str, value: Json) -> Result<Json, String> {
+        if self.input[self.position..].starts_with(text.as_bytes()) {
+            self.position += text.len();
+            Ok(value)
+        } else {
+            Err(format!("Invalid literal at offset {}", self.position))
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        let mut float = false;
+        while let Some(ch) = self.peek() {
+            match ch {
+                b'0'..=b'9' => {}
+                b'.' | b'e' | b'E' | b'+' | b'-' => float = true,
+                _ => break,
+            }
+            self.position += 1;
+        }
+        let text = std::str::from_utf8(&self.input[start..self.position]).unwrap_or("");
+        let invalid = || format!("Invalid number at offset {}", start);
+        if float {
+            text.parse().map(Json::Float).map_err(|_| invalid())
+        } else {
+            match text.parse() {
+                Ok(value) => Ok(Json::Int(value)),
+                Err(_) => text.parse().map(Json::Float).map_err(|_| invalid()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.position += 1; // consume `"`
+        let mut bytes = Vec::new();
+        loop {
+            let Some(ch) = self.peek() else {
+                return Err("Unterminated string".to_string());
+            };
+            self.position += 1;
+            match ch {
+                b'"' => break,
+                b'\\' => {
+                    let escape = self.peek().ok_or("Unterminated string")?;
+                    self.position += 1;
+                    match escape {
+                        b'"' => bytes.push(b'"'),
+                        b'\\' => bytes.push(b'\\'),
+                        b'/' => bytes.push(b'/'),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0c),
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'u' => {
+                            let ch = self.unicode_escape()?;
+                            let mut buffer = [0; 4];
+                            bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+                        }
+                        _ => return Err(format!("Invalid escape at offset {}", self.position - 1)),
+                    }
+                }
+                _ => bytes.push(ch),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| "Invalid UTF-8 in string".to_string())
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex4()?;
+        if (0xD800..0xDC00).contains(&high) {
+            // A surrogate pair: the low half follows as another `\u` escape.
+            if self.input[self.position..].starts_with(b"\\u") {
+                self.position += 2;
+                let low = self.hex4()?;
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                return Ok(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+            return Ok('\u{FFFD}');
+        }
+        Ok(char::from_u32(high).unwrap_or('\u{FFFD}'))
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits = self.input
+            .get(self.position..self.position + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| format!("Invalid unicode escape at offset {}", self.position))?;
+        self.position += 4;
+        Ok(digits)
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.position += 1; // consume `[`
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(format!("Expected , or ] at offset {}", self.position)),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.position += 1; // consume `{`
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(format!("Expected string key at offset {}", self.position));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Err(format!("Expected : at offset {}", self.position));
+            }
+            self.position += 1;
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(format!("Expected , or }} at offset {}", self.position)),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+}
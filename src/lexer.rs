@@ -1,4 +1,5 @@
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenType {
     Number(i64),
     Ident(String),
@@ -13,22 +14,49 @@ pub enum TokenType {
     Lt,
     GtEq,
     LtEq,
+    PlusAssign,
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    Colon,
+    Comma,
+    LParen,
+    RParen,
+    Const,
+    Var,
     EOF,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
     pub token_type: TokenType,
     pub literal: String,
     pub line: usize,
 }
 
+/// A `#` comment, which the lexer skips but keeps for tools such as the
+/// formatter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comment {
+    /// The text after `#`, without the line break.
+    pub text: String,
+    pub line: usize,
+    /// Whether the comment follows a token on the same line.
+    pub trailing: bool,
+}
+
 pub struct Lexer {
     input: Vec<u8>,
     position: usize,
     read_position: usize,
     ch: u8,
     line: usize,
+    /// Byte offset where the current line starts.
+    line_start: usize,
+    last_token_line: usize,
+    token_column: usize,
+    comments: Vec<Comment>,
 }
 
 impl Lexer {
@@ -39,6 +67,10 @@ impl Lexer {
             read_position: 0,
             ch: 0,
             line: 1,
+            line_start: 0,
+            last_token_line: 0,
+            token_column: 0,
+            comments: Vec::new(),
         };
         lexer.read_char();
         lexer
@@ -58,6 +90,7 @@ impl Lexer {
 
         if prev_ch == b'\n' {
             self.line += 1;
+            self.line_start = self.position;
         }
     }
 
@@ -83,13 +116,49 @@ impl Lexer {
             .to_string()
     }
 
-    pub fn next_token(&mut self) -> Token {
-        // Skip whitespace
-        while matches!(self.ch, b' ' | b'\t' | b'\n' | b'\r') {
+    fn read_comment(&mut self) {
+        let line = self.line;
+        self.read_char(); // consume `#`
+        let start = self.position;
+        while !matches!(self.ch, b'\n' | 0) {
             self.read_char();
         }
+        let text = String::from_utf8_lossy(&self.input[start..self.position])
+            .trim_end()
+            .to_string();
+        self.comments.push(Comment {
+            text,
+            line,
+            trailing: self.last_token_line == line,
+        });
+    }
+
+    /// Comments skipped so far.
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Byte offset in its line where the token last returned by `next_token`
+    /// starts.
+    pub fn column(&self) -> usize {
+        self.token_column
+    }
+
+    pub fn next_token(&mut self) -> Token {
+        // Skip whitespace and comments
+        loop {
+            while matches!(self.ch, b' ' | b'\t' | b'\n' | b'\r') {
+                self.read_char();
+            }
+            if self.ch != b'#' {
+                break;
+            }
+            self.read_comment();
+        }
 
         let current_line = self.line;
+        self.last_token_line = current_line;
+        self.token_column = self.position - self.line_start;
 
         match self.ch {
             b'=' => {
@@ -162,36 +231,104 @@ impl Lexer {
                 }
             }
             b'+' => {
+                self.read_char();
+                if self.ch == b'=' {
+                    self.read_char();
+                    Token {
+                        token_type: TokenType::PlusAssign,
+                        literal: "+=".to_string(),
+                        line: current_line,
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::Plus,
+                        literal: "+".to_string(),
+                        line: current_line,
+                    }
+                }
+            }
+            b'-' => {
+                self.read_char();
+                if self.ch == b'=' {
+                    self.read_char();
+                    Token {
+                        token_type: TokenType::MinusAssign,
+                        literal: "-=".to_string(),
+                        line: current_line,
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::Minus,
+                        literal: "-".to_string(),
+                        line: current_line,
+                    }
+                }
+            }
+            b'*' => {
+                self.read_char();
+                if self.ch == b'=' {
+                    self.read_char();
+                    Token {
+                        token_type: TokenType::StarAssign,
+                        literal: "*=".to_string(),
+                        line: current_line,
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::Star,
+                        literal: "*".to_string(),
+                        line: current_line,
+                    }
+                }
+            }
+            b'/' => {
+                self.read_char();
+                if self.ch == b'=' {
+                    self.read_char();
+                    Token {
+                        token_type: TokenType::SlashAssign,
+                        literal: "/=".to_string(),
+                        line: current_line,
+                    }
+                } else {
+                    Token {
+                        token_type: TokenType::Slash,
+                        literal: "/".to_string(),
+                        line: current_line,
+                    }
+                }
+            }
+            b':' => {
                 let tok = Token {
-                    token_type: TokenType::Plus,
-                    literal: "+".to_string(),
+                    token_type: TokenType::Colon,
+                    literal: ":".to_string(),
                     line: current_line,
                 };
                 self.read_char();
                 tok
             }
-            b'-' => {
+            b',' => {
                 let tok = Token {
-                    token_type: TokenType::Minus,
-                    literal: "-".to_string(),
+                    token_type: TokenType::Comma,
+                    literal: ",".to_string(),
                     line: current_line,
                 };
                 self.read_char();
                 tok
             }
-            b'*' => {
+            b'(' => {
                 let tok = Token {
-                    token_type: TokenType::Star,
-                    literal: "*".to_string(),
+                    token_type: TokenType::LParen,
+                    literal: "(".to_string(),
                     line: current_line,
                 };
                 self.read_char();
                 tok
             }
-            b'/' => {
+            b')' => {
                 let tok = Token {
-                    token_type: TokenType::Slash,
-                    literal: "/".to_string(),
+                    token_type: TokenType::RParen,
+                    literal: ")".to_string(),
                     line: current_line,
                 };
                 self.read_char();
@@ -209,7 +346,7 @@ impl Lexer {
             b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
                 let literal = self.read_ident();
                 Token {
-                    token_type: TokenType::Ident(literal.clone()),
+                    token_type: lookup_keyword(&literal),
                     literal,
                     line: current_line,
                 }
@@ -232,7 +369,22 @@ impl Lexer {
     }
 }
 
+fn lookup_keyword(ident: &str) -> TokenType {
+    match ident {
+        "const" => TokenType::Const,
+        "var" => TokenType::Var,
+        _ => TokenType::Ident(ident.to_string()),
+    }
+}
+
+#[allow(clippy::result_unit_err)]
 pub fn tokenize(input: String) -> Result<Vec<Token>, ()> {
+    tokenize_with_comments(input).map(|(tokens, _)| tokens)
+}
+
+/// Like `tokenize`, but also returns the comments found in the input.
+#[allow(clippy::result_unit_err)]
+pub fn tokenize_with_comments(input: String) -> Result<(Vec<Token>, Vec<Comment>), ()> {
     let mut lexer = Lexer::new(input);
     let mut tokens = Vec::new();
 
@@ -245,5 +397,25 @@ pub fn tokenize(input: String) -> Result<Vec<Token>, ()> {
         }
     }
 
+    Ok((tokens, lexer.comments))
+}
+
+/// Like `tokenize`, but pairs each token with the byte offset in its line
+/// where it starts, for tools that point at tokens.
+#[allow(clippy::result_unit_err)]
+pub fn tokenize_with_columns(input: String) -> Result<Vec<(Token, usize)>, ()> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+
+    loop {
+        let token = lexer.next_token();
+        let end = matches!(token.token_type, TokenType::EOF);
+        tokens.push((token, lexer.column()));
+
+        if end {
+            break;
+        }
+    }
+
     Ok(tokens)
-}
\ No newline at end of file
+}
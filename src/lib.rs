@@ -1,4 +1,18 @@
 pub mod lexer;
 pub mod ast;
 pub mod parser;
-pub mod interpreter;
\ No newline at end of file
+pub mod interpreter;
+pub mod output;
+pub mod typecheck;
+pub mod resolver;
+pub mod compiler;
+pub mod vm;
+pub mod optimize;
+pub mod codegen;
+pub mod format;
+pub mod json;
+pub mod lsp;
+pub mod debugger;
+pub mod dap;
+pub mod trace;
+pub mod dump;
\ No newline at end of file
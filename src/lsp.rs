@@ -0,0 +1,512 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Read, Write};
+use std::time::Duration;
+use crate::interpreter::{Interpreter, Limits};
+use crate::json::Json;
+use crate::lexer::{tokenize, tokenize_with_columns, Token, TokenType};
+use crate::parser::Parser;
+use crate::typecheck::TypeChecker;
+
+const SYMBOL_KIND_VARIABLE: i64 = 13;
+const SYMBOL_KIND_CONSTANT: i64 = 14;
+const COMPLETION_KIND_VARIABLE: i64 = 6;
+const COMPLETION_KIND_KEYWORD: i64 = 14;
+const COMPLETION_KIND_CONSTANT: i64 = 21;
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const KEYWORDS: &[&str] = &["const", "var", "entero", "booleano"];
+
+/// Largest message body `read_message` accepts, so a bad header cannot make
+/// the server allocate an arbitrary amount of memory.
+pub const MAX_CONTENT_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Limits for the run that finds the value shown on hover, so a large
+/// document cannot stall the server.
+const HOVER_LIMITS: Limits = Limits {
+    max_steps: Some(100_000),
+    max_nesting: None,
+    max_variables: None,
+    timeout: Some(Duration::from_millis(200)),
+};
+
+/// Runs a language server over a pair of streams until the client sends
+/// `exit` or closes its end, returning the process exit code.
+///
+/// A message that cannot be read, because of a bad header or a body that is
+/// not JSON, is answered with a parse error and the server keeps reading.
+pub fn run<R: BufRead, W: Write>(mut reader: R, mut writer: W) -> io::Result<i32> {
+    let mut server = Server::new();
+    loop {
+        let message = match read_message(&mut reader) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                write_message(&mut writer, &error_response(Json::Null, PARSE_ERROR, e.to_string()))?;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        for reply in server.handle(&message) {
+            write_message(&mut writer, &reply)?;
+        }
+        if let Some(code) = server.exit_code() {
+            return Ok(code);
+        }
+    }
+    Ok(1)
+}
+
+/// Reads one `Content-Length` framed message, or `None` at end of input.
+///
+/// A malformed message is an `InvalidData` error. Its body has been consumed
+/// when the header gave its length, so the next call reads the next message.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Json>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length header"))?;
+    if length > MAX_CONTENT_LENGTH {
+        io::copy(&mut Read::take(&mut *reader, length as u64), &mut io::sink())?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Content-Length {} exceeds the limit of {} bytes", length, MAX_CONTENT_LENGTH),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Json::parse(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &Json) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Language server state: the open documents, keyed by URI.
+///
+/// Documents are synchronized in full on every change and analyzed again for
+/// each request, which is cheap for programs of this size.
+pub struct Server {
+    documents: HashMap<String, String>,
+    shutdown: bool,
+    exit_code: Option<i32>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Server {
+            documents: HashMap::new(),
+            shutdown: false,
+            exit_code: None,
+        }
+    }
+
+    /// Set once the client has sent `exit`: 0 after a `shutdown` request,
+    /// 1 otherwise.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Handles one request or notification, returning the messages to send
+    /// back: the response to a request, and any diagnostics to publish.
+    pub fn handle(&mut self, message: &Json) -> Vec<Json> {
+        let method = message.get("method").and_then(Json::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Json::Null);
+        let Some(id) = message.get("id").cloned() else {
+            return self.notification(method, &params);
+        };
+
+        let result = match method {
+            "initialize" => Ok(initialize_result()),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Json::Null)
+            }
+            "textDocument/definition" => self.with_position(&params, definition),
+            "textDocument/hover" => self.with_position(&params, hover),
+            "textDocument/documentSymbol" => self
+                .document(&params)
+                .map(|(_, text)| document_symbols(text)),
+            "textDocument/completion" => self
+                .document(&params)
+                .map(|(_, text)| completions(text)),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+        };
+
+        let response = match result {
+            Ok(result) => Json::object([
+                ("jsonrpc", Json::string("2.0")),
+                ("id", id),
+                ("result", result),
+            ]),
+            Err((code, message)) => error_response(id, code, message),
+        };
+        vec![response]
+    }
+
+    fn notification(&mut self, method: &str, params: &Json) -> Vec<Json> {
+        let uri = params.pointer(&["textDocument", "uri"]).and_then(Json::as_str);
+        match (method, uri) {
+            ("exit", _) => {
+                self.exit_code = Some(if self.shutdown { 0 } else { 1 });
+                Vec::new()
+            }
+            ("textDocument/didOpen", Some(uri)) => {
+                let text = params.pointer(&["textDocument", "text"]).and_then(Json::as_str).unwrap_or("");
+                self.documents.insert(uri.to_string(), text.to_string());
+                vec![publish_diagnostics(uri, text)]
+            }
+            ("textDocument/didChange", Some(uri)) => {
+                // Full synchronization: the last change holds the whole text.
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Json::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Json::as_str);
+                match text {
+                    Some(text) => {
+                        self.documents.insert(uri.to_string(), text.to_string());
+                        vec![publish_diagnostics(uri, text)]
+                    }
+                    None => Vec::new(),
+                }
+            }
+            ("textDocument/didClose", Some(uri)) => {
+                self.documents.remove(uri);
+                vec![publish_diagnostics(uri, "")]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn document<'a>(&'a self, params: &Json) -> Result<(&'a str, &'a str), (i64, String)> {
+        let uri = params
+            .pointer(&["textDocument", "uri"])
+            .and_then(Json::as_str)
+            .ok_or((INVALID_PARAMS, "Missing textDocument.uri".to_string()))?;
+        self.documents
+            .get_key_value(uri)
+            .map(|(uri, text)| (uri.as_str(), text.as_str()))
+            .ok_or((INVALID_PARAMS, format!("Unknown document: {}", uri)))
+    }
+
+    fn with_position(
+        &self,
+        params: &Json,
+        request: fn(&str, &str, Position) -> Json,
+    ) -> Result<Json, (i64, String)> {
+        let (uri, text) = self.document(params)?;
+        let line = params.pointer(&["position", "line"]).and_then(Json::as_i64);
+        let character = params.pointer(&["position", "character"]).and_then(Json::as_i64);
+        match (line, character) {
+            (Some(line), Some(character)) => Ok(request(uri, text, Position {
+                line: line as usize,
+                character: character as usize,
+            })),
+            _ => Err((INVALID_PARAMS, "Missing position".to_string())),
+        }
+    }
+}
+
+fn error_response(id: Json, code: i64, message: String) -> Json {
+    Json::object([
+        ("jsonrpc", Json::string("2.0")),
+        ("id", id),
+        ("error", Json::object([("code", Json::Int(code)), ("message", Json::String(message))])),
+    ])
+}
+
+fn initialize_result() -> Json {
+    Json::object([
+        ("capabilities", Json::object([
+            ("textDocumentSync", Json::Int(1)),
+            ("hoverProvider", Json::Bool(true)),
+            ("definitionProvider", Json::Bool(true)),
+            ("documentSymbolProvider", Json::Bool(true)),
+            ("completionProvider", Json::object([])),
+        ])),
+        ("serverInfo", Json::object([
+            ("name", Json::string("pseudocu-lsp")),
+            ("version", Json::string(env!("CARGO_PKG_VERSION"))),
+        ])),
+    ])
+}
+
+/// A zero-based LSP position; `character` counts UTF-16 code units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position {
+    line: usize,
+    character: usize,
+}
+
+/// An identifier in the source, with its zero-based line and the byte range
+/// it covers in that line.
+struct Occurrence {
+    name: String,
+    line: usize,
+    start: usize,
+    end: usize,
+    /// Whether this occurrence gives the variable a value or declares it.
+    definition: bool,
+    constant: bool,
+}
+
+/// Finds every identifier token in the source.
+fn occurrences(tokens: &[(Token, usize)]) -> Vec<Occurrence> {
+    let mut occurrences = Vec::new();
+
+    for (index, (token, start)) in tokens.iter().enumerate() {
+        let TokenType::Ident(name) = &token.token_type else {
+            continue;
+        };
+        let previous = index.checked_sub(1).map(|index| &tokens[index].0.token_type);
+        let next = tokens.get(index + 1).map(|(token, _)| &token.token_type);
+        let declared = matches!(previous, Some(TokenType::Const | TokenType::Var));
+        let assigned = !matches!(previous, Some(TokenType::Colon))
+            && matches!(
+                next,
+                Some(TokenType::Assign | TokenType::PlusAssign | TokenType::MinusAssign
+                    | TokenType::StarAssign | TokenType::SlashAssign)
+            );
+        occurrences.push(Occurrence {
+            name: name.clone(),
+            line: token.line.saturating_sub(1),
+            start: *start,
+            end: start + name.len(),
+            definition: declared || assigned,
+            constant: matches!(previous, Some(TokenType::Const)),
+        });
+    }
+    occurrences
+}
+
+/// Converts a byte offset in a line to a UTF-16 column.
+fn utf16_column(line: &str, byte: usize) -> usize {
+    line.get(..byte).map_or(byte, |prefix| prefix.encode_utf16().count())
+}
+
+fn line_text(text: &str, line: usize) -> &str {
+    text.lines().nth(line).unwrap_or("")
+}
+
+fn range(text: &str, line: usize, start: usize, end: usize) -> Json {
+    let line_text = line_text(text, line);
+    let position = |byte| Json::object([
+        ("line", Json::Int(line as i64)),
+        ("character", Json::Int(utf16_column(line_text, byte) as i64)),
+    ]);
+    Json::object([("start", position(start)), ("end", position(end))])
+}
+
+fn occurrence_range(text: &str, occurrence: &Occurrence) -> Json {
+    range(text, occurrence.line, occurrence.start, occurrence.end)
+}
+
+/// The range covering the code on a line, without indentation or comment.
+fn line_range(text: &str, line: usize) -> Json {
+    let line_text = line_text(text, line);
+    let code = line_text.split('#').next().unwrap_or("");
+    let start = code.len() - code.trim_start().len();
+    let end = code.trim_end().len().max(start);
+    range(text, line, start, end)
+}
+
+fn occurrence_at<'a>(text: &str, occurrences: &'a [Occurrence], position: Position) -> Option<&'a Occurrence> {
+    let line_text = line_text(text, position.line);
+    occurrences.iter().find(|occurrence| {
+        occurrence.line == position.line
+            && utf16_column(line_text, occurrence.start) <= position.character
+            && position.character <= utf16_column(line_text, occurrence.end)
+    })
+}
+
+/// Splits the ` at line N` suffix off a lexer or parser error.
+fn split_line(message: &str) -> (&str, Option<usize>) {
+    match message.rsplit_once(" at line ") {
+        Some((message, line)) => match line.parse() {
+            Ok(line) => (message, Some(line)),
+            Err(_) => (message, None),
+        },
+        None => (message, None),
+    }
+}
+
+fn diagnostic(range: Json, message: &str) -> Json {
+    Json::object([
+        ("range", range),
+        ("severity", Json::Int(1)),
+        ("source", Json::string("pseudocu")),
+        ("message", Json::string(message)),
+    ])
+}
+
+/// Lexer and parser errors for a document.
+fn diagnostics(text: &str) -> Vec<Json> {
+    let tokens = match tokenize_with_columns(text.to_string()) {
+        Ok(tokens) => tokens,
+        Err(error) => return vec![diagnostic(line_range(text, 0), &format!("Lexer error: {:?}", error))],
+    };
+
+    let mut diagnostics = Vec::new();
+    // The lexer ends the token stream at a character it does not know,
+    // reporting it as an `EOF` token that carries the character.
+    if let Some((token, start)) = tokens.last()
+        && !token.literal.is_empty()
+    {
+        let range = range(text, token.line.saturating_sub(1), *start, start + token.literal.len());
+        diagnostics.push(diagnostic(range, &format!("Unexpected character: {}", token.literal)));
+    }
+
+    let tokens = tokens.into_iter().map(|(token, _)| token).collect();
+    if let Err(error) = Parser::new(tokens).parse() {
+        let (message, line) = split_line(&error);
+        let line = line.unwrap_or(1).saturating_sub(1);
+        diagnostics.push(diagnostic(line_range(text, line), message));
+    }
+    diagnostics
+}
+
+fn publish_diagnostics(uri: &str, text: &str) -> Json {
+    Json::object([
+        ("jsonrpc", Json::string("2.0")),
+        ("method", Json::string("textDocument/publishDiagnostics")),
+        ("params", Json::object([
+            ("uri", Json::string(uri)),
+            ("diagnostics", Json::Array(diagnostics(text))),
+        ])),
+    ])
+}
+
+fn analyze(text: &str) -> Vec<Occurrence> {
+    let tokens = tokenize_with_columns(text.to_string()).unwrap_or_default();
+    occurrences(&tokens)
+}
+
+/// Goes to the first assignment or declaration of the variable under the
+/// cursor.
+fn definition(uri: &str, text: &str, position: Position) -> Json {
+    let occurrences = analyze(text);
+    let Some(target) = occurrence_at(text, &occurrences, position) else {
+        return Json::Null;
+    };
+    match occurrences.iter().find(|occurrence| occurrence.definition && occurrence.name == target.name) {
+        Some(definition) => Json::object([
+            ("uri", Json::string(uri)),
+            ("range", occurrence_range(text, definition)),
+        ]),
+        None => Json::Null,
+    }
+}
+
+/// Shows the type the checker infers for the variable under the cursor and,
+/// when the program runs without errors within `HOVER_LIMITS`, its final
+/// value.
+fn hover(_uri: &str, text: &str, position: Position) -> Json {
+    let occurrences = analyze(text);
+    let Some(target) = occurrence_at(text, &occurrences, position) else {
+        return Json::Null;
+    };
+    if KEYWORDS.contains(&target.name.as_str()) {
+        return Json::Null;
+    }
+
+    let constant = occurrences.iter().any(|occurrence| occurrence.constant && occurrence.name == target.name);
+    let mut signature = format!("{} {}", if constant { "const" } else { "var" }, target.name);
+    if let Ok(tokens) = tokenize(text.to_string())
+        && let Ok(program) = Parser::new(tokens).parse()
+    {
+        let mut checker = TypeChecker::new();
+        let _ = checker.check(&program);
+        if let Some(ty) = checker.variable_type(&target.name) {
+            signature.push_str(&format!(": {}", ty));
+        }
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(HOVER_LIMITS);
+        if interpreter.run(&program).is_ok()
+            && let Some(value) = interpreter.get_variable(&target.name)
+        {
+            signature.push_str(&format!(" = {}", value));
+        }
+    }
+
+    Json::object([
+        ("contents", Json::object([
+            ("kind", Json::string("markdown")),
+            ("value", Json::String(format!("```pseudocu\n{}\n```", signature))),
+        ])),
+        ("range", occurrence_range(text, target)),
+    ])
+}
+
+/// One symbol per variable, at its first assignment or declaration.
+fn document_symbols(text: &str) -> Json {
+    let occurrences = analyze(text);
+    let mut seen = Vec::new();
+    let mut symbols = Vec::new();
+    for occurrence in occurrences.iter().filter(|occurrence| occurrence.definition) {
+        if seen.contains(&occurrence.name) {
+            continue;
+        }
+        seen.push(occurrence.name.clone());
+        let kind = if occurrence.constant { SYMBOL_KIND_CONSTANT } else { SYMBOL_KIND_VARIABLE };
+        symbols.push(Json::object([
+            ("name", Json::string(occurrence.name.as_str())),
+            ("kind", Json::Int(kind)),
+            ("range", line_range(text, occurrence.line)),
+            ("selectionRange", occurrence_range(text, occurrence)),
+        ]));
+    }
+    Json::Array(symbols)
+}
+
+/// Every identifier in the document, followed by the keywords.
+fn completions(text: &str) -> Json {
+    let occurrences = analyze(text);
+    let mut items = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    for occurrence in &occurrences {
+        if seen.contains(&occurrence.name.as_str()) || KEYWORDS.contains(&occurrence.name.as_str()) {
+            continue;
+        }
+        seen.push(&occurrence.name);
+        let constant = occurrences.iter().any(|other| other.constant && other.name == occurrence.name);
+        let kind = if constant { COMPLETION_KIND_CONSTANT } else { COMPLETION_KIND_VARIABLE };
+        items.push(Json::object([
+            ("label", Json::string(occurrence.name.as_str())),
+            ("kind", Json::Int(kind)),
+        ]));
+    }
+    for keyword in KEYWORDS {
+        items.push(Json::object([
+            ("label", Json::string(*keyword)),
+            ("kind", Json::Int(COMPLETION_KIND_KEYWORD)),
+        ]));
+    }
+    Json::Array(items)
+}
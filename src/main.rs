@@ -1,25 +1,131 @@
 use std::env;
 use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
 use pseudocu::lexer;
 use pseudocu::parser;
-use pseudocu::interpreter;
+use pseudocu::interpreter::{self, Limits};
+use pseudocu::compiler;
+use pseudocu::vm;
+use pseudocu::optimize;
+use pseudocu::codegen;
+use pseudocu::format;
+use pseudocu::debugger::Debugger;
+use pseudocu::dap;
+use pseudocu::dump;
+use pseudocu::trace::{Trace, TraceFormat};
+use pseudocu::typecheck;
+use pseudocu::resolver::{self, Severity};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    
-    let input = if args.len() > 1 {
-        match fs::read_to_string(&args[1]) {
+    if env::args().nth(1).as_deref() == Some("fmt") {
+        run_fmt(env::args().skip(2));
+        return;
+    }
+    if env::args().nth(1).as_deref() == Some("dap") {
+        let stdin = std::io::stdin();
+        if let Err(e) = dap::run(stdin.lock(), std::io::stdout().lock()) {
+            eprintln!("Debug adapter error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut path = None;
+    let mut strict = false;
+    let mut check = false;
+    let mut use_vm = false;
+    let mut optimize = false;
+    let mut dump_optimized = false;
+    let mut emit = None;
+    let mut debug = false;
+    let mut trace = None;
+    let mut dump_ast = None;
+    let mut dump_tokens = false;
+    let mut limits = Limits::default();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "--check" => check = true,
+            "--vm" => use_vm = true,
+            "--optimize" => optimize = true,
+            "--dump-optimized" => dump_optimized = true,
+            "--debug" => debug = true,
+            "--dump-ast" => dump_ast = Some("text".to_string()),
+            _ if arg.starts_with("--dump-ast=") => dump_ast = Some(arg["--dump-ast=".len()..].to_string()),
+            "--dump-tokens" => dump_tokens = true,
+            "--max-steps" => limits.max_steps = Some(parse_option(&arg, args.next())),
+            "--max-nesting" => limits.max_nesting = Some(parse_option(&arg, args.next())),
+            "--max-variables" => limits.max_variables = Some(parse_option(&arg, args.next())),
+            "--timeout" => {
+                let seconds: f64 = parse_option(&arg, args.next());
+                match Duration::try_from_secs_f64(seconds) {
+                    Ok(timeout) => limits.timeout = Some(timeout),
+                    Err(_) => {
+                        eprintln!("Invalid value for --timeout: {}", seconds);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "--trace" => trace = Some(TraceFormat::Text),
+            _ if arg.starts_with("--trace=") => match TraceFormat::parse(&arg["--trace=".len()..]) {
+                Some(format) => trace = Some(format),
+                None => {
+                    eprintln!("Unknown trace format: {} (expected text, csv or markdown)", &arg["--trace=".len()..]);
+                    std::process::exit(1);
+                }
+            },
+            "--emit" => match args.next() {
+                Some(target) => emit = Some(target),
+                None => {
+                    eprintln!("Missing target for --emit");
+                    std::process::exit(1);
+                }
+            },
+            _ if arg.starts_with("--") => {
+                eprintln!("Unknown option: {}", arg);
+                std::process::exit(1);
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    // `--dump-optimized` is `--optimize --dump-ast`, in the format chosen
+    // with `--dump-ast=FORMAT` if given.
+    if dump_optimized {
+        optimize = true;
+        dump_ast.get_or_insert_with(|| "text".to_string());
+    }
+
+    let input = if let Some(path) = &path {
+        match fs::read_to_string(path) {
             Ok(content) => content,
             Err(e) => {
-                eprintln!("Error reading file '{}': {}", args[1], e);
+                eprintln!("Error reading file '{}': {}", path, e);
                 std::process::exit(1);
             }
         }
     } else {
-        eprintln!("Usage: pseudocu <file.pc>");
+        eprintln!("Usage: pseudocu [--strict] [--check] [--vm] [--optimize] [--dump-optimized] [--dump-ast[=json]] [--dump-tokens] [--debug] [--max-steps N] [--max-nesting N] [--max-variables N] [--timeout SECONDS] [--trace[=text|csv|markdown]] [--emit python|c|js|asm|wat|dot|mermaid] <file.pc>");
         std::process::exit(1);
     };
 
+    if debug && use_vm {
+        eprintln!("--debug cannot be combined with --vm");
+        std::process::exit(1);
+    }
+    if use_vm && limits != Limits::default() {
+        eprintln!("Execution limits are not supported with --vm");
+        std::process::exit(1);
+    }
+    if trace.is_some() && (use_vm || debug) {
+        eprintln!("--trace cannot be combined with --vm or --debug");
+        std::process::exit(1);
+    }
+    let source = input.clone();
+
     let tokens = match lexer::tokenize(input) {
         Ok(t) => t,
         Err(e) => {
@@ -28,6 +134,11 @@ fn main() {
         }
     };
 
+    if dump_tokens {
+        print!("{}", dump::tokens(&tokens));
+        return;
+    }
+
     let mut parser = parser::Parser::new(tokens);
     let program = match parser.parse() {
         Ok(p) => p,
@@ -37,11 +148,212 @@ fn main() {
         }
     };
 
+    if let Some(format) = dump_ast {
+        // Show the tree that would run.
+        let program = if optimize { optimize::optimize(&program) } else { program };
+        match format.as_str() {
+            "text" => print!("{}", dump::ast_tree(&program)),
+            "json" => println!("{}", dump::ast_json(&program)),
+            _ => {
+                eprintln!("Unknown AST dump format: {} (expected text or json)", format);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut resolver = resolver::Resolver::new();
+    resolver.set_strict_mode(strict);
+    let diagnostics = resolver.resolve(&program);
+    let has_errors = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error);
+
+    if check {
+        for diagnostic in &diagnostics {
+            match diagnostic.severity {
+                Severity::Error => eprintln!("Error: {}", diagnostic),
+                Severity::Warning => eprintln!("Warning: {}", diagnostic),
+            }
+        }
+        let type_errors = typecheck::check(&program).err().unwrap_or_default();
+        for error in &type_errors {
+            eprintln!("Type error: {}", error);
+        }
+        if has_errors || !type_errors.is_empty() {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Outside `--check`, a resolver error reports the same way the
+    // interpreter would have failed on it.
+    if has_errors {
+        for diagnostic in &diagnostics {
+            if diagnostic.severity == Severity::Error {
+                eprintln!("Runtime error: {}", diagnostic.message);
+            }
+        }
+        std::process::exit(1);
+    }
+
+    // Folding constants removes nesting, so the limit is checked on the
+    // source for an optimized run to reject the same programs.
+    if optimize
+        && emit.is_none()
+        && let Err(e) = limits.check_nesting(&program)
+    {
+        eprintln!("Runtime error: {}", e);
+        std::process::exit(1);
+    }
+
+    let program = if optimize {
+        optimize::optimize(&program)
+    } else {
+        program
+    };
+
+    if let Some(target) = emit {
+        let code = match target.as_str() {
+            "python" => codegen::python::generate(&program),
+            "c" => codegen::c::generate(&program),
+            "js" => codegen::js::generate(&program),
+            "asm" => codegen::asm::generate(&program),
+            "wat" => codegen::wat::generate(&program),
+            "dot" => codegen::dot::generate(&program),
+            "mermaid" => codegen::mermaid::generate(&program),
+            _ => {
+                eprintln!("Unknown emit target: {}", target);
+                std::process::exit(1);
+            }
+        };
+        print!("{}", code);
+        return;
+    }
+
+    if use_vm {
+        let chunk = compiler::compile(&program);
+        let mut vm = vm::Vm::new();
+        vm.set_strict_mode(strict);
+        if let Err(e) = vm.run(&chunk) {
+            eprintln!("Runtime error: {}", e);
+            std::process::exit(1);
+        }
+        print_variables(vm.print_variables());
+        return;
+    }
+
     let mut interpreter = interpreter::Interpreter::new();
-    if let Err(e) = interpreter.run(program) {
+    interpreter.set_strict_mode(strict);
+    interpreter.set_limits(limits);
+
+    if debug {
+        let stdin = std::io::stdin();
+        let mut debugger = Debugger::new(&source, stdin.lock(), std::io::stdout());
+        if let Err(e) = interpreter.run_with_hook(&program, &mut debugger) {
+            eprintln!("Runtime error: {}", e);
+            std::process::exit(1);
+        }
+        if !debugger.quit_requested() {
+            print_variables(interpreter.print_variables());
+        }
+        return;
+    }
+
+    if let Some(format) = trace {
+        let mut table = Trace::new();
+        let result = interpreter.run_with_hook(&program, &mut table);
+        print!("{}", table.render(format));
+        if let Err(e) = result {
+            eprintln!("Runtime error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Err(e) = interpreter.run(&program) {
         eprintln!("Runtime error: {}", e);
         std::process::exit(1);
     }
 
-    interpreter.print_variables();
-}
\ No newline at end of file
+    print_variables(interpreter.print_variables());
+}
+
+/// Reports a failure to write the final variable table. A closed pipe, as
+/// in `pseudocu program.pc | head -1`, is not an error.
+fn print_variables(result: std::io::Result<()>) {
+    if let Err(e) = result
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        eprintln!("Error writing output: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// `pseudocu fmt [--check] <file.pc>...`: rewrites each file in canonical
+/// form, or with `--check` only reports the files that would change.
+fn run_fmt(args: impl Iterator<Item = String>) {
+    let mut check = false;
+    let mut paths = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ if arg.starts_with("--") => {
+                eprintln!("Unknown option: {}", arg);
+                std::process::exit(1);
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("Usage: pseudocu fmt [--check] <file.pc>...");
+        std::process::exit(1);
+    }
+
+    let mut unformatted = false;
+    for path in &paths {
+        let input = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading file '{}': {}", path, e);
+                std::process::exit(1);
+            }
+        };
+        let formatted = match format::format_source(&input) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("Parser error in '{}': {}", path, e);
+                std::process::exit(1);
+            }
+        };
+        if formatted == input {
+            continue;
+        }
+        if check {
+            eprintln!("{} is not formatted", path);
+            unformatted = true;
+        } else if let Err(e) = fs::write(path, formatted) {
+            eprintln!("Error writing file '{}': {}", path, e);
+            std::process::exit(1);
+        }
+    }
+    if unformatted {
+        std::process::exit(1);
+    }
+}
+
+/// Parses the value following an option such as `--max-steps`, exiting with
+/// an error if it is missing or invalid.
+fn parse_option<T: FromStr>(option: &str, value: Option<String>) -> T {
+    let Some(value) = value else {
+        eprintln!("Missing value for {}", option);
+        std::process::exit(1);
+    };
+    match value.parse() {
+        Ok(value) => value,
+        Err(_) => {
+            eprintln!("Invalid value for {}: {}", option, value);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use crate::ast::{Expr, Operator, Program, Statement};
+use crate::interpreter::apply_operator;
+
+/// Returns an optimized copy of a program.
+///
+/// Constant `BinaryOp` subtrees are folded, arithmetic identities such as
+/// `x * 1` and `x + 0` are simplified, and expression statements whose value
+/// is a constant are removed. A subtree that would fail at runtime, such as a
+/// division by zero, is left untouched so the error is still raised by the
+/// statement that contains it.
+pub fn optimize(program: &Program) -> Program {
+    let mut statements = Vec::new();
+    let mut lines = Vec::new();
+
+    for (index, statement) in program.statements.iter().enumerate() {
+        let statement = optimize_statement(statement);
+        if matches!(statement, Statement::Expr(Expr::Number(_))) {
+            continue;
+        }
+        statements.push(statement);
+        if let Some(line) = program.line(index) {
+            lines.push(line);
+        }
+    }
+
+    Program { statements, lines }
+}
+
+pub fn optimize_statement(statement: &Statement) -> Statement {
+    match statement {
+        Statement::Assign { name, value } => Statement::Assign {
+            name: name.clone(),
+            value: optimize_expr(value),
+        },
+        Statement::Const { name, value } => Statement::Const {
+            name: name.clone(),
+            value: optimize_expr(value),
+        },
+        Statement::Declare { name, type_annotation, value } => Statement::Declare {
+            name: name.clone(),
+            type_annotation: *type_annotation,
+            value: value.as_ref().map(optimize_expr),
+        },
+        Statement::CompoundAssign { name, operator, value } => Statement::CompoundAssign {
+            name: name.clone(),
+            operator: operator.clone(),
+            value: optimize_expr(value),
+        },
+        Statement::Expr(expr) => Statement::Expr(optimize_expr(expr)),
+    }
+}
+
+pub fn optimize_expr(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Number(_) | Expr::Identifier(_) => expr.clone(),
+        // A host function may have effects, so calls are never folded.
+        Expr::Call { name, args } => Expr::Call {
+            name: name.clone(),
+            args: args.iter().map(optimize_expr).collect(),
+        },
+        Expr::BinaryOp { left, operator, right } => {
+            let left = optimize_expr(left);
+            let right = optimize_expr(right);
+
+            if let (Expr::Number(l), Expr::Number(r)) = (&left, &right)
+                && let Ok(value) = apply_operator(operator, *l, *r)
+            {
+                return Expr::Number(value);
+            }
+
+            match (operator, &left, &right) {
+                (Operator::Plus, _, Expr::Number(0))
+                | (Operator::Minus, _, Expr::Number(0))
+                | (Operator::Multiply, _, Expr::Number(1))
+                | (Operator::Divide, _, Expr::Number(1)) => left,
+                (Operator::Plus, Expr::Number(0), _)
+                | (Operator::Multiply, Expr::Number(1), _) => right,
+                _ => Expr::BinaryOp {
+                    left: Box::new(left),
+                    operator: operator.clone(),
+                    right: Box::new(right),
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Where the interpreter and the VM write program output, stdout unless the
+/// host calls `set_output`. It is `Send` so an interpreter can be moved to a
+/// worker thread.
+pub type Output = Box<dyn Write + Send>;
+
+/// An in-memory output sink whose clones share one buffer, so a host can
+/// hand a clone to `set_output` and read what was written afterwards, from
+/// any thread.
+#[derive(Clone, Default)]
+pub struct SharedBuffer {
+    bytes: Arc<Mutex<Vec<u8>>>,
+}
+
+impl SharedBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far, with invalid UTF-8 replaced.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.bytes()).into_owned()
+    }
+
+    /// Returns the contents and empties the buffer.
+    pub fn take(&self) -> String {
+        let bytes = std::mem::take(&mut *self.bytes());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    /// Locks the buffer. A writer that panicked cannot leave the bytes in an
+    /// invalid state, so a poisoned lock is used as is.
+    fn bytes(&self) -> MutexGuard<'_, Vec<u8>> {
+        self.bytes.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes `name = value` lines sorted by name, the variable table printed
+/// after a run.
+pub(crate) fn write_table<'a, W: Write>(
+    out: &mut W,
+    variables: impl Iterator<Item = (&'a str, i64)>,
+) -> io::Result<()> {
+    let mut vars: Vec<_> = variables.collect();
+    vars.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, value) in vars {
+        writeln!(out, "{} = {}", name, value)?;
+    }
+    Ok(())
+}
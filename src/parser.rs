@@ -1,10 +1,12 @@
-use crate::ast::{Expr, Operator, Program, Statement};
+use std::collections::HashSet;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
 use crate::lexer::{Token, TokenType};
 
 pub struct Parser {
     tokens: Vec<Token>,
     position: usize,
     current_token: Token,
+    constants: HashSet<String>,
 }
 
 impl Parser {
@@ -17,6 +19,7 @@ impl Parser {
                 literal: String::new(),
                 line: 0,
             },
+            constants: HashSet::new(),
         };
         parser.advance();
         parser
@@ -40,6 +43,13 @@ impl Parser {
         self.parse_comparison()
     }
 
+    /// Whether every token has been consumed. The lexer also ends the stream
+    /// with an `EOF` token at a character it does not recognize, which does
+    /// not count as the end.
+    pub fn is_at_end(&self) -> bool {
+        matches!(self.current_token.token_type, TokenType::EOF) && self.current_token.literal.is_empty()
+    }
+
     fn parse_comparison(&mut self) -> Result<Expr, String> {
         let mut expr = self.parse_addition()?;
 
@@ -126,9 +136,26 @@ impl Parser {
             }
             TokenType::Ident(name) => {
                 let name = name.clone();
+                let line = self.current_token.line;
                 self.advance();
+                if self.at_call(line) {
+                    return self.parse_call(name);
+                }
                 Ok(Expr::Identifier(name))
             }
+            TokenType::LParen => {
+                self.advance(); // consume `(`
+                let expr = self.parse_expression()?;
+                if !matches!(self.current_token.token_type, TokenType::RParen) {
+                    return Err(format!(
+                        "Expected ) after expression, found: {:?} at line {}",
+                        self.current_token.token_type,
+                        self.current_token.line
+                    ));
+                }
+                self.advance(); // consume `)`
+                Ok(expr)
+            }
             _ => Err(format!(
                 "Expected number or identifier, found: {:?} at line {}",
                 self.current_token.token_type,
@@ -137,15 +164,163 @@ impl Parser {
         }
     }
 
+    /// Whether a `(` follows an identifier on `line`. A parenthesis on the
+    /// next line starts a new expression statement instead.
+    fn at_call(&self, line: usize) -> bool {
+        matches!(self.current_token.token_type, TokenType::LParen) && self.current_token.line == line
+    }
+
+    fn parse_call(&mut self, name: String) -> Result<Expr, String> {
+        self.advance(); // consume `(`
+        let mut args = Vec::new();
+        if !matches!(self.current_token.token_type, TokenType::RParen) {
+            loop {
+                args.push(self.parse_expression()?);
+                if !matches!(self.current_token.token_type, TokenType::Comma) {
+                    break;
+                }
+                self.advance(); // consume `,`
+            }
+        }
+        if !matches!(self.current_token.token_type, TokenType::RParen) {
+            return Err(format!(
+                "Expected , or ) in call to {}, found: {:?} at line {}",
+                name,
+                self.current_token.token_type,
+                self.current_token.line
+            ));
+        }
+        self.advance(); // consume `)`
+        Ok(Expr::Call { name, args })
+    }
+
+    fn compound_operator(&self) -> Option<Operator> {
+        match self.current_token.token_type {
+            TokenType::PlusAssign => Some(Operator::Plus),
+            TokenType::MinusAssign => Some(Operator::Minus),
+            TokenType::StarAssign => Some(Operator::Multiply),
+            TokenType::SlashAssign => Some(Operator::Divide),
+            _ => None,
+        }
+    }
+
+    fn parse_const(&mut self) -> Result<Statement, String> {
+        self.advance(); // consume `const`
+
+        let line = self.current_token.line;
+        let name = self.expect_identifier("const")?;
+
+        if !matches!(self.current_token.token_type, TokenType::Assign) {
+            return Err(format!(
+                "Expected = after const {}, found: {:?} at line {}",
+                name,
+                self.current_token.token_type,
+                self.current_token.line
+            ));
+        }
+        self.advance(); // consume `=`
+
+        if !self.constants.insert(name.clone()) {
+            return Err(format!("Cannot assign to constant: {} at line {}", name, line));
+        }
+        let value = self.parse_expression()?;
+        Ok(Statement::Const { name, value })
+    }
+
+    fn parse_var(&mut self) -> Result<Statement, String> {
+        self.advance(); // consume `var`
+
+        let line = self.current_token.line;
+        let name = self.expect_identifier("var")?;
+        self.check_not_constant(&name, line)?;
+
+        let type_annotation = if matches!(self.current_token.token_type, TokenType::Colon) {
+            self.advance(); // consume `:`
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        let value = if matches!(self.current_token.token_type, TokenType::Assign) {
+            self.advance(); // consume `=`
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        Ok(Statement::Declare { name, type_annotation, value })
+    }
+
+    fn parse_type(&mut self) -> Result<Type, String> {
+        let ty = match &self.current_token.token_type {
+            TokenType::Ident(name) if name == "entero" => Type::Integer,
+            TokenType::Ident(name) if name == "booleano" => Type::Boolean,
+            _ => {
+                return Err(format!(
+                    "Expected type (entero or booleano), found: {:?} at line {}",
+                    self.current_token.token_type,
+                    self.current_token.line
+                ))
+            }
+        };
+        self.advance();
+        Ok(ty)
+    }
+
+    fn expect_identifier(&mut self, after: &str) -> Result<String, String> {
+        match &self.current_token.token_type {
+            TokenType::Ident(name) => {
+                let name = name.clone();
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(format!(
+                "Expected identifier after {}, found: {:?} at line {}",
+                after,
+                self.current_token.token_type,
+                self.current_token.line
+            )),
+        }
+    }
+
+    fn check_not_constant(&self, name: &str, line: usize) -> Result<(), String> {
+        if self.constants.contains(name) {
+            Err(format!("Cannot assign to constant: {} at line {}", name, line))
+        } else {
+            Ok(())
+        }
+    }
+
     fn parse_statement(&mut self) -> Result<Statement, String> {
+        match self.current_token.token_type {
+            TokenType::Const => return self.parse_const(),
+            TokenType::Var => return self.parse_var(),
+            _ => {}
+        }
+
         if let TokenType::Ident(name) = &self.current_token.token_type {
             let name = name.clone();
+            let line = self.current_token.line;
+            let start = self.position;
             self.advance();
-            
-            if matches!(self.current_token.token_type, TokenType::Assign) {
+
+            if self.at_call(line) {
+                // A statement such as `f(x) + 1`: parse it again from the
+                // name as a whole expression.
+                self.position = start - 1;
+                self.advance();
+                let expr = self.parse_expression()?;
+                return Ok(Statement::Expr(expr));
+            } else if matches!(self.current_token.token_type, TokenType::Assign) {
+                self.check_not_constant(&name, line)?;
                 self.advance(); // consume `=`
                 let value = self.parse_expression()?;
                 return Ok(Statement::Assign { name, value });
+            } else if let Some(operator) = self.compound_operator() {
+                self.check_not_constant(&name, line)?;
+                self.advance(); // consume `+=`, `-=`, `*=` or `/=`
+                let value = self.parse_expression()?;
+                return Ok(Statement::CompoundAssign { name, operator, value });
             } else {
 
                 let expr = Expr::Identifier(name);
@@ -159,12 +334,14 @@ impl Parser {
 
     pub fn parse(&mut self) -> Result<Program, String> {
         let mut statements = Vec::new();
+        let mut lines = Vec::new();
 
         while !matches!(self.current_token.token_type, TokenType::EOF) {
+            lines.push(self.current_token.line);
             let stmt = self.parse_statement()?;
             statements.push(stmt);
         }
 
-        Ok(Program { statements })
+        Ok(Program { statements, lines })
     }
 }
\ No newline at end of file
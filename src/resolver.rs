@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::ast::{Expr, Program, Statement};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} at line {}", self.message, line),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+struct Binding {
+    line: Option<usize>,
+    declared: bool,
+    initialized: bool,
+    used: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    bindings: HashMap<String, Binding>,
+    order: Vec<String>,
+}
+
+/// Resolves every name in a program before it runs, reporting reads of
+/// variables that have no value yet as errors, and unused variables of nested
+/// scopes and shadowed declarations as warnings.
+///
+/// The resolver keeps a stack of scopes. Programs only have the global scope
+/// today; constructs that introduce their own scope should wrap their body in
+/// `begin_scope`/`end_scope`.
+pub struct Resolver {
+    scopes: Vec<Scope>,
+    diagnostics: Vec<Diagnostic>,
+    strict: bool,
+    line: Option<usize>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Resolver {
+            scopes: vec![Scope::default()],
+            diagnostics: Vec::new(),
+            strict: false,
+            line: None,
+        }
+    }
+
+    /// Mirrors `Interpreter::set_strict_mode`: assigning to a variable that
+    /// was never declared becomes an error.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    pub fn resolve(mut self, program: &Program) -> Vec<Diagnostic> {
+        for (index, statement) in program.statements.iter().enumerate() {
+            self.line = program.line(index);
+            self.resolve_statement(statement);
+        }
+        self.finish()
+    }
+
+    /// Closes every open scope and returns the diagnostics ordered by line.
+    pub fn finish(mut self) -> Vec<Diagnostic> {
+        while !self.scopes.is_empty() {
+            self.end_scope();
+        }
+        self.diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        self.diagnostics
+    }
+
+    pub fn begin_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    pub fn end_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+        // Globals are printed when the program finishes, so they are never
+        // unused.
+        if self.scopes.is_empty() {
+            return;
+        }
+        for name in scope.order {
+            let binding = &scope.bindings[&name];
+            if !binding.used {
+                self.diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    message: format!("Unused variable: {}", name),
+                    line: binding.line,
+                });
+            }
+        }
+    }
+
+    pub fn resolve_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Assign { name, value } => {
+                self.resolve_expr(value);
+                self.assign(name);
+            }
+            Statement::Const { name, value } => {
+                self.resolve_expr(value);
+                self.declare(name, true);
+            }
+            Statement::Declare { name, value, .. } => {
+                if let Some(value) = value {
+                    self.resolve_expr(value);
+                }
+                self.declare(name, value.is_some());
+            }
+            Statement::CompoundAssign { name, value, .. } => {
+                self.read(name);
+                self.resolve_expr(value);
+                self.assign(name);
+            }
+            Statement::Expr(expr) => self.resolve_expr(expr),
+        }
+    }
+
+    pub fn resolve_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(_) => {}
+            Expr::Identifier(name) => self.read(name),
+            Expr::BinaryOp { left, right, .. } => {
+                self.resolve_expr(left);
+                self.resolve_expr(right);
+            }
+            Expr::Call { args, .. } => {
+                for arg in args {
+                    self.resolve_expr(arg);
+                }
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, initialized: bool) {
+        let line = self.line;
+        let depth = self.scopes.len() - 1;
+
+        if self.scopes[depth].bindings.contains_key(name) {
+            self.error(format!("Variable already defined: {}", name));
+            return;
+        }
+        if self.scopes[..depth].iter().any(|scope| scope.bindings.contains_key(name)) {
+            self.warning(format!("Declaration of {} shadows an outer variable", name));
+        }
+
+        let scope = &mut self.scopes[depth];
+        scope.order.push(name.to_string());
+        scope.bindings.insert(name.to_string(), Binding {
+            line,
+            declared: true,
+            initialized,
+            used: false,
+        });
+    }
+
+    fn assign(&mut self, name: &str) {
+        let line = self.line;
+        match self.lookup(name) {
+            Some(binding) => {
+                let declared = binding.declared;
+                binding.initialized = true;
+                if self.strict && !declared {
+                    self.error(format!("Undeclared variable: {}", name));
+                }
+            }
+            None => {
+                if self.strict {
+                    self.error(format!("Undeclared variable: {}", name));
+                }
+                let scope = self.scopes.last_mut().expect("global scope");
+                scope.order.push(name.to_string());
+                scope.bindings.insert(name.to_string(), Binding {
+                    line,
+                    declared: false,
+                    initialized: true,
+                    used: false,
+                });
+            }
+        }
+    }
+
+    fn read(&mut self, name: &str) {
+        match self.lookup(name) {
+            Some(binding) => {
+                binding.used = true;
+                if !binding.initialized {
+                    self.error(format!("Uninitialized variable: {}", name));
+                }
+            }
+            None => self.error(format!("Undefined variable: {}", name)),
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.bindings.get_mut(name))
+    }
+
+    fn error(&mut self, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            message,
+            line: self.line,
+        });
+    }
+
+    fn warning(&mut self, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            message,
+            line: self.line,
+        });
+    }
+}
+
+pub fn resolve(program: &Program) -> Vec<Diagnostic> {
+    Resolver::new().resolve(program)
+}
@@ -0,0 +1,157 @@
+use std::ops::ControlFlow;
+use crate::ast::Program;
+use crate::interpreter::{ExecutionHook, Interpreter};
+
+/// How `Trace::render` lays out a trace table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceFormat {
+    /// Columns padded to line up, for the terminal.
+    Text,
+    Csv,
+    Markdown,
+}
+
+impl TraceFormat {
+    /// Parses the value of `--trace=FORMAT`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(TraceFormat::Text),
+            "csv" => Some(TraceFormat::Csv),
+            "markdown" | "md" => Some(TraceFormat::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// The state of the program after one executed statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceRow {
+    /// Source line of the statement, if the program records lines.
+    pub line: Option<usize>,
+    /// Every initialized variable, in the order the interpreter first saw
+    /// the names, which is the order of `Interpreter::variables`. A name
+    /// declared without a value comes before one assigned earlier, so use
+    /// `Trace::columns` for the order of first assignment.
+    pub variables: Vec<(String, i64)>,
+}
+
+/// A trace table: one row per executed statement.
+///
+/// Record one by running a program with a `Trace` as the execution hook.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    pub rows: Vec<TraceRow>,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Trace { rows: Vec::new() }
+    }
+
+    /// Variable names in the order they first appear in the trace.
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns: Vec<&str> = Vec::new();
+        for row in &self.rows {
+            for (name, _) in &row.variables {
+                if !columns.contains(&name.as_str()) {
+                    columns.push(name);
+                }
+            }
+        }
+        columns
+    }
+
+    pub fn render(&self, format: TraceFormat) -> String {
+        let table = self.cells();
+        match format {
+            TraceFormat::Text => render_text(&table),
+            TraceFormat::Csv => table.iter().map(|row| row.join(",") + "\n").collect(),
+            TraceFormat::Markdown => render_markdown(&table),
+        }
+    }
+
+    /// The header and rows as text; variables not yet assigned are blank.
+    fn cells(&self) -> Vec<Vec<String>> {
+        let columns = self.columns();
+        let mut table = vec![
+            std::iter::once("Line").chain(columns.iter().copied()).map(str::to_string).collect(),
+        ];
+        for row in &self.rows {
+            let mut cells = vec![row.line.map_or(String::new(), |line| line.to_string())];
+            cells.extend(columns.iter().map(|column| {
+                row.variables
+                    .iter()
+                    .find(|(name, _)| name == column)
+                    .map_or(String::new(), |(_, value)| value.to_string())
+            }));
+            table.push(cells);
+        }
+        table
+    }
+}
+
+impl ExecutionHook for Trace {
+    fn before_statement(&mut self, _interpreter: &Interpreter, _program: &Program, _index: usize) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn after_statement(&mut self, interpreter: &Interpreter, program: &Program, index: usize) {
+        self.rows.push(TraceRow {
+            line: program.line(index),
+            variables: interpreter
+                .variables()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        });
+    }
+}
+
+fn column_widths(table: &[Vec<String>]) -> Vec<usize> {
+    (0..table[0].len())
+        .map(|column| table.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect()
+}
+
+fn pad_cells(row: &[String], widths: &[usize]) -> Vec<String> {
+    row.iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:>width$}", cell, width = width))
+        .collect()
+}
+
+/// Right-aligns every column, with a rule under the header:
+///
+/// ```text
+/// Line | x |  y
+/// -----+---+---
+///    1 | 2 |
+///    2 | 2 | 6
+/// ```
+fn render_text(table: &[Vec<String>]) -> String {
+    let widths = column_widths(table);
+    let mut output = String::new();
+    for (index, row) in table.iter().enumerate() {
+        output.push_str(pad_cells(row, &widths).join(" | ").trim_end());
+        output.push('\n');
+        if index == 0 {
+            let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+            output.push_str(&rule.join("-+-"));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+fn render_markdown(table: &[Vec<String>]) -> String {
+    // The alignment row needs at least three characters per column.
+    let widths: Vec<usize> = column_widths(table).into_iter().map(|width| width.max(3)).collect();
+    let mut output = String::new();
+    for (index, row) in table.iter().enumerate() {
+        output.push_str(&format!("| {} |\n", pad_cells(row, &widths).join(" | ")));
+        if index == 0 {
+            let rule: Vec<String> = widths.iter().map(|width| format!("{}:", "-".repeat(width - 1))).collect();
+            output.push_str(&format!("| {} |\n", rule.join(" | ")));
+        }
+    }
+    output
+}
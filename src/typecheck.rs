@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::ast::{Expr, Operator, Program, Statement, Type};
+use crate::interpreter::apply_operator;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeError {
+    pub message: String,
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for TypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} at line {}", self.message, line),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+struct VariableType {
+    declared: Option<Type>,
+    current: Option<Type>,
+    /// The value the variable holds, when it is known ahead of execution.
+    value: Option<i64>,
+}
+
+/// Infers the type of every variable and expression ahead of execution.
+///
+/// `booleano` values are also valid `entero` values, so the only mismatch the
+/// checker can find is an integer flowing into a variable declared `booleano`.
+/// Programs have no control flow yet, so the type of a variable is tracked
+/// statement by statement and is exact. Values computed from literals are
+/// tracked too, so arithmetic such as `1 * 1` keeps the type of its result,
+/// and arithmetic on `booleano` values whose result is not known has no type
+/// rather than being rejected.
+pub struct TypeChecker {
+    variables: HashMap<String, VariableType>,
+    errors: Vec<TypeError>,
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TypeChecker {
+    pub fn new() -> Self {
+        TypeChecker {
+            variables: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn check(&mut self, program: &Program) -> Result<(), Vec<TypeError>> {
+        for (index, statement) in program.statements.iter().enumerate() {
+            self.check_statement(statement, program.line(index));
+        }
+
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(std::mem::take(&mut self.errors))
+        }
+    }
+
+    fn check_statement(&mut self, statement: &Statement, line: Option<usize>) {
+        match statement {
+            Statement::Assign { name, value } => self.assign(name, value, line),
+            Statement::Const { name, value } => {
+                let ty = self.infer_expr(value);
+                let value = self.constant(value);
+                self.variables.insert(name.clone(), VariableType {
+                    declared: None,
+                    current: ty,
+                    value,
+                });
+            }
+            Statement::Declare { name, type_annotation, value } => {
+                self.variables.insert(name.clone(), VariableType {
+                    declared: *type_annotation,
+                    current: None,
+                    value: None,
+                });
+                if let Some(value) = value {
+                    self.assign(name, value, line);
+                }
+            }
+            Statement::CompoundAssign { name, operator, value } => {
+                let value = Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier(name.clone())),
+                    operator: operator.clone(),
+                    right: Box::new(value.clone()),
+                };
+                self.assign(name, &value, line);
+            }
+            Statement::Expr(_) => {}
+        }
+    }
+
+    fn assign(&mut self, name: &str, value: &Expr, line: Option<usize>) {
+        let ty = self.infer_expr(value);
+        let value = self.constant(value);
+        let variable = self.variables
+            .entry(name.to_string())
+            .or_insert(VariableType { declared: None, current: None, value: None });
+
+        if let (Some(declared), Some(actual)) = (variable.declared, ty)
+            && !is_assignable(actual, declared)
+        {
+            self.errors.push(TypeError {
+                message: format!(
+                    "Type mismatch: cannot assign {} to {} of type {}",
+                    actual, name, declared
+                ),
+                line,
+            });
+        }
+        variable.current = ty;
+        variable.value = value;
+    }
+
+    /// Returns `None` when the type cannot be known, e.g. for an undefined
+    /// variable, which is not a type error.
+    pub fn infer_expr(&self, expr: &Expr) -> Option<Type> {
+        match expr {
+            Expr::Number(0) | Expr::Number(1) => Some(Type::Boolean),
+            Expr::Number(_) => Some(Type::Integer),
+            Expr::Identifier(name) => self.variable_type(name),
+            Expr::BinaryOp { left, operator, right } => match operator {
+                Operator::Plus | Operator::Minus | Operator::Multiply | Operator::Divide => {
+                    match self.constant(expr) {
+                        Some(0 | 1) => Some(Type::Boolean),
+                        Some(_) => Some(Type::Integer),
+                        // `booleano` operands and values of unknown type, such
+                        // as calls, may or may not give `0` or `1`.
+                        None if self.infer_expr(left) != Some(Type::Integer)
+                            && self.infer_expr(right) != Some(Type::Integer) => None,
+                        None => Some(Type::Integer),
+                    }
+                }
+                Operator::Eq | Operator::NotEq | Operator::Gt | Operator::Lt
+                | Operator::GtEq | Operator::LtEq => Some(Type::Boolean),
+            },
+            // Host functions may return any value.
+            Expr::Call { .. } => None,
+        }
+    }
+
+    /// The value of an expression when it only depends on literals and
+    /// variables with known values, and evaluating it does not fail.
+    fn constant(&self, expr: &Expr) -> Option<i64> {
+        match expr {
+            Expr::Number(value) => Some(*value),
+            Expr::Identifier(name) => self.variables.get(name).and_then(|variable| variable.value),
+            Expr::BinaryOp { left, operator, right } => {
+                apply_operator(operator, self.constant(left)?, self.constant(right)?).ok()
+            }
+            Expr::Call { .. } => None,
+        }
+    }
+
+    /// The declared type of a variable, or the type of the value it currently
+    /// holds when it was not declared with an annotation.
+    pub fn variable_type(&self, name: &str) -> Option<Type> {
+        self.variables
+            .get(name)
+            .and_then(|variable| variable.declared.or(variable.current))
+    }
+}
+
+fn is_assignable(actual: Type, declared: Type) -> bool {
+    match declared {
+        Type::Integer => true,
+        Type::Boolean => actual == Type::Boolean,
+    }
+}
+
+pub fn check(program: &Program) -> Result<(), Vec<TypeError>> {
+    TypeChecker::new().check(program)
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use crate::ast::{Operator, Type};
+use crate::compiler::{Chunk, Instruction};
+use crate::interpreter::{apply_operator, check_type, HostFunction};
+use crate::output::{write_table, Output};
+
+#[derive(Clone, Default)]
+struct Slot {
+    value: Option<i64>,
+    defined: bool,
+    constant: bool,
+    declared: bool,
+    declared_type: Option<Type>,
+}
+
+/// Stack-based virtual machine executing a `Chunk` produced by the compiler.
+/// It follows the same semantics and error messages as `Interpreter`.
+pub struct Vm {
+    stack: Vec<i64>,
+    slots: Vec<Slot>,
+    names: Vec<String>,
+    strict: bool,
+    functions: HashMap<String, HostFunction>,
+    output: Output,
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        Vm {
+            stack: Vec::new(),
+            slots: Vec::new(),
+            names: Vec::new(),
+            strict: false,
+            functions: HashMap::new(),
+            output: Box::new(io::stdout()),
+        }
+    }
+
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Makes a Rust function callable from programs, like
+    /// `Interpreter::register_function`.
+    pub fn register_function<F>(&mut self, name: &str, function: F)
+    where
+        F: Fn(&[i64]) -> Result<i64, String> + Send + Sync + 'static,
+    {
+        self.functions.insert(name.to_string(), Box::new(function));
+    }
+
+    /// Sends program output to `output` instead of stdout, like
+    /// `Interpreter::set_output`.
+    pub fn set_output<W: Write + Send + 'static>(&mut self, output: W) {
+        self.output = Box::new(output);
+    }
+
+    pub fn run(&mut self, chunk: &Chunk) -> Result<(), String> {
+        self.stack.clear();
+        self.slots = vec![Slot::default(); chunk.names.len()];
+        self.names = chunk.names.clone();
+
+        for instruction in &chunk.code {
+            self.execute(chunk, *instruction)?;
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, chunk: &Chunk, instruction: Instruction) -> Result<(), String> {
+        match instruction {
+            Instruction::Constant(index) => self.stack.push(chunk.constants[index]),
+            Instruction::Load(slot) => {
+                let value = self.read_slot(slot)?;
+                self.stack.push(value);
+            }
+            Instruction::Store(slot) => {
+                let value = self.pop();
+                if let Some(ty) = self.slots[slot].declared_type {
+                    check_type(&self.names[slot], ty, value)?;
+                }
+                let slot = &mut self.slots[slot];
+                slot.value = Some(value);
+                slot.defined = true;
+            }
+            Instruction::DefineConst(slot) => {
+                let value = self.pop();
+                self.slots[slot] = Slot {
+                    value: Some(value),
+                    defined: true,
+                    constant: true,
+                    declared: true,
+                    declared_type: None,
+                };
+            }
+            Instruction::Declare { slot, type_annotation, initialized } => {
+                let value = if initialized { Some(self.pop()) } else { None };
+                if let (Some(ty), Some(value)) = (type_annotation, value) {
+                    check_type(&self.names[slot], ty, value)?;
+                }
+                self.slots[slot] = Slot {
+                    value,
+                    defined: true,
+                    constant: false,
+                    declared: true,
+                    declared_type: type_annotation,
+                };
+            }
+            Instruction::CheckAssignable(slot) => {
+                self.check_not_constant(slot)?;
+                if self.strict && !self.slots[slot].declared {
+                    return Err(format!("Undeclared variable: {}", self.names[slot]));
+                }
+            }
+            Instruction::CheckNotConstant(slot) => self.check_not_constant(slot)?,
+            Instruction::CheckUndefined(slot) => {
+                if self.slots[slot].defined {
+                    return Err(format!("Variable already defined: {}", self.names[slot]));
+                }
+            }
+            Instruction::Add => self.binary(&Operator::Plus)?,
+            Instruction::Subtract => self.binary(&Operator::Minus)?,
+            Instruction::Multiply => self.binary(&Operator::Multiply)?,
+            Instruction::Divide => self.binary(&Operator::Divide)?,
+            Instruction::Equal => self.binary(&Operator::Eq)?,
+            Instruction::NotEqual => self.binary(&Operator::NotEq)?,
+            Instruction::Greater => self.binary(&Operator::Gt)?,
+            Instruction::Less => self.binary(&Operator::Lt)?,
+            Instruction::GreaterEqual => self.binary(&Operator::GtEq)?,
+            Instruction::LessEqual => self.binary(&Operator::LtEq)?,
+            Instruction::Call { function, args } => {
+                let values = self.stack.split_off(self.stack.len() - args);
+                let name = &chunk.functions[function];
+                let value = match self.functions.get(name) {
+                    Some(function) => function(&values)?,
+                    None => return Err(format!("Undefined function: {}", name)),
+                };
+                self.stack.push(value);
+            }
+            Instruction::Pop => {
+                self.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn binary(&mut self, operator: &Operator) -> Result<(), String> {
+        let right = self.pop();
+        let left = self.pop();
+        let value = apply_operator(operator, left, right)?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn pop(&mut self) -> i64 {
+        self.stack.pop().expect("stack underflow")
+    }
+
+    fn read_slot(&self, slot: usize) -> Result<i64, String> {
+        let state = &self.slots[slot];
+        match state.value {
+            Some(value) => Ok(value),
+            None if state.defined => Err(format!("Uninitialized variable: {}", self.names[slot])),
+            None => Err(format!("Undefined variable: {}", self.names[slot])),
+        }
+    }
+
+    fn check_not_constant(&self, slot: usize) -> Result<(), String> {
+        if self.slots[slot].constant {
+            Err(format!("Cannot assign to constant: {}", self.names[slot]))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<i64> {
+        self.names
+            .iter()
+            .position(|slot_name| slot_name == name)
+            .and_then(|slot| self.slots[slot].value)
+    }
+
+    /// Every variable that holds a value, in slot order.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, i64)> {
+        with_values(&self.names, &self.slots)
+    }
+
+    /// Writes `name = value` for every variable, sorted by name.
+    pub fn write_variables<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write_table(out, self.variables())
+    }
+
+    /// The table `print_variables` writes, as a string.
+    pub fn variables_table(&self) -> String {
+        let mut out = Vec::new();
+        self.write_variables(&mut out).expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("the table is valid UTF-8")
+    }
+
+    /// Writes the variable table to the output, stdout unless changed with
+    /// `set_output`.
+    pub fn print_variables(&mut self) -> io::Result<()> {
+        write_table(&mut self.output, with_values(&self.names, &self.slots))?;
+        self.output.flush()
+    }
+}
+
+fn with_values<'a>(names: &'a [String], slots: &'a [Slot]) -> impl Iterator<Item = (&'a str, i64)> {
+    names.iter()
+        .zip(slots)
+        .filter_map(|(name, slot)| slot.value.map(|value| (name.as_str(), value)))
+}
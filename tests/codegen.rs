@@ -0,0 +1,490 @@
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+    use pseudocu::ast::Program;
+    use pseudocu::codegen::{asm, c, dot, js, mermaid, python, wat};
+    use pseudocu::codegen::flowchart::{Flowchart, Shape};
+    use pseudocu::interpreter::Interpreter;
+    use pseudocu::lexer::tokenize;
+    use pseudocu::output::SharedBuffer;
+    use pseudocu::parser::Parser;
+
+    /// Programs whose generated code is run and compared with the
+    /// interpreter, with the variables each one defines, which is what the
+    /// WebAssembly `run` returns.
+    const PARITY_CASES: &[(&str, &[&str])] = &[
+        ("d = 5\na = 4\nresult = d + a", &["a", "d", "result"]),
+        ("a = 0 - 7\nb = a / 2\nc = 7 / 0 - 2\n", &["a", "b", "c"]),
+        ("a = 0 - 7\nb = a / 2\nc = b * 3 - a / 3 + 1\nd = c >= b", &["a", "b", "c", "d"]),
+        ("const K = 3\nvar f: booleano = 1\nx = 10\nx += K * 2\nf = x > 100\ny = x / 3 - 1", &["K", "f", "x", "y"]),
+        ("var f: booleano = 1\nf += 1", &["f"]),
+        ("x = 2\ny = f(x, x * 3) + 1", &["x", "y"]),
+        ("x = 2\nf(x / 0)", &["x"]),
+    ];
+
+    /// Programs that overflow `i64`, checked by the backends that reproduce
+    /// the interpreter's `Integer overflow` error.
+    const OVERFLOW_CASES: &[&str] = &[
+        "a = 9223372036854775807\nb = a + 1",
+        "a = 0 - 9223372036854775807 - 1\nm = 0 - 1\nb = a / m",
+    ];
+
+    fn parse_program(input: &str) -> Program {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse().unwrap()
+    }
+
+    /// What the interpreter prints for a program: the variable table, or
+    /// the error it stops with.
+    fn interpreter_output(program: &Program) -> Result<String, String> {
+        let output = SharedBuffer::new();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(output.clone());
+        interpreter.run(program)?;
+        interpreter.print_variables().unwrap();
+        Ok(output.contents())
+    }
+
+    /// Runs a command, returning stdout on success and the last line of
+    /// stderr on failure. A missing `tool` fails the test: the tests that
+    /// need external tools are `#[ignore]`d and only run on request.
+    fn run_tool(tool: &str, command: &mut Command) -> Result<String, String> {
+        let output = command
+            .output()
+            .unwrap_or_else(|error| panic!("cannot run {}: {}", tool, error));
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if output.status.success() {
+            Ok(stdout)
+        } else {
+            Err(stderr.trim_end().lines().last().unwrap_or("").to_string())
+        }
+    }
+
+    /// The parity cases, the overflow cases every native backend checks, and
+    /// `extra` cases.
+    fn parity_sources(extra: &[&'static str]) -> impl Iterator<Item = &'static str> {
+        PARITY_CASES
+            .iter()
+            .map(|(source, _)| *source)
+            .chain(OVERFLOW_CASES.iter().copied())
+            .chain(extra.to_vec())
+    }
+
+    /// Generates, builds and runs every program with `execute`, which gets
+    /// the index of the program for naming files, and checks that it prints
+    /// the same output as the interpreter.
+    fn assert_output_matches_interpreter<'a>(
+        sources: impl Iterator<Item = &'a str>,
+        execute: impl Fn(usize, &Program) -> Result<String, String>,
+    ) {
+        for (index, source) in sources.enumerate() {
+            let program = parse_program(source);
+            let expected = interpreter_output(&program)
+                .map_err(|error| format!("Runtime error: {}", error));
+            assert_eq!(execute(index, &program), expected, "program: {}", source);
+        }
+    }
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("pseudocu-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    // ==================== Python Tests ====================
+
+    #[test]
+    fn test_python_simple_program() {
+        let code = python::generate(&parse_program("d = 5\na = 4\nresult = d + a"));
+        assert_eq!(
+            code,
+            "def main():\n    d = 5\n    a = 4\n    result = d + a\n    print(f\"a = {a}\")\n    print(f\"d = {d}\")\n    print(f\"result = {result}\")\n\n\nif __name__ == \"__main__\":\n    main()\n"
+        );
+    }
+
+    #[test]
+    fn test_python_comparison_returns_integer() {
+        let code = python::generate(&parse_program("x = 3 > 2"));
+        assert!(code.contains("    x = int(3 > 2)\n"));
+    }
+
+    #[test]
+    fn test_python_division_helper() {
+        let code = python::generate(&parse_program("x = 7 / 2"));
+        assert!(code.contains("def _pc_div(a, b):"));
+        assert!(code.contains("    q = abs(a) // abs(b)\n"));
+        assert!(code.contains("    x = _pc_div(7, 2)\n"));
+        assert!(code.contains("raise PseudocuError(\"Division by zero\")"));
+    }
+
+    #[test]
+    fn test_python_compound_and_declarations() {
+        let code = python::generate(&parse_program(
+            "const K = 2\nvar total: entero = 0\nvar ready: booleano\ntotal += K * 3\nready = total"
+        ));
+        assert!(code.contains("    K = 2\n"));
+        assert!(code.contains("    total: int = 0\n"));
+        assert!(code.contains("    ready: int\n"));
+        assert!(code.contains("    total = total + K * 3\n"));
+        assert!(code.contains("    ready = _pc_booleano(\"ready\", total)\n"));
+    }
+
+    #[test]
+    fn test_python_reserved_names() {
+        let code = python::generate(&parse_program("print = 1\nclass = print + 1"));
+        assert!(code.contains("    _pc_print = 1\n"));
+        assert!(code.contains("    _pc_class = _pc_print + 1\n"));
+        assert!(code.contains("    print(f\"class = {_pc_class}\")\n"));
+    }
+
+    #[test]
+    fn test_python_empty_program() {
+        let code = python::generate(&parse_program(""));
+        assert!(code.contains("def main():\n    pass\n"));
+    }
+
+    #[test]
+    #[ignore = "needs python3"]
+    fn test_python_output_matches_interpreter() {
+        assert_output_matches_interpreter(PARITY_CASES.iter().map(|(source, _)| *source), |index, program| {
+            let path = write_temp(&format!("{}.py", index), &python::generate(program));
+            run_tool("python3", Command::new("python3").arg(&path))
+        });
+    }
+
+    // ==================== C Tests ====================
+
+    #[test]
+    fn test_c_declares_long_long_variables() {
+        let code = c::generate(&parse_program("var x: entero\ny = 2\nx = y * 3"));
+        assert!(code.contains("    long long x = 0;\n    long long y = 0;\n"));
+        assert!(code.contains("    y = 2LL;\n    x = pc_mul(y, 3LL);\n"));
+        assert!(code.contains("    printf(\"x = %lld\\n\", x);\n    printf(\"y = %lld\\n\", y);\n"));
+    }
+
+    #[test]
+    fn test_c_checked_division() {
+        let code = c::generate(&parse_program("x = 7 / 2"));
+        assert!(code.contains("static long long pc_div(long long a, long long b) {"));
+        assert!(code.contains("if (b == 0) pc_error(\"Division by zero\");"));
+        assert!(code.contains("    x = pc_div(7LL, 2LL);\n"));
+        assert!(!code.contains("pc_add"));
+    }
+
+    #[test]
+    fn test_c_left_operand_first_when_both_can_fail() {
+        let code = c::generate(&parse_program("x = 1 / 0 + 2 * 3\ny = 1 / 2 + 3"));
+        assert!(code.contains("    long long pc_t0 = pc_div(1LL, 0LL);\n    x = pc_add(pc_t0, pc_mul(2LL, 3LL));\n"));
+        assert!(code.contains("    y = pc_add(pc_div(1LL, 2LL), 3LL);\n"));
+    }
+
+    #[test]
+    fn test_c_comparisons() {
+        let code = c::generate(&parse_program("x = 1 < 2 == 3 > 4"));
+        assert!(code.contains("    x = ((1LL < 2LL) == 3LL) > 4LL;\n"));
+    }
+
+    #[test]
+    fn test_c_reserved_names() {
+        let code = c::generate(&parse_program("int = 1\nEOF = int + 1\npc_x = 2"));
+        assert!(code.contains("    pc_int = 1LL;\n"));
+        assert!(code.contains("    pc_EOF = pc_add(pc_int, 1LL);\n"));
+        assert!(code.contains("    pc_pc_x = 2LL;\n"));
+        assert!(code.contains("    printf(\"int = %lld\\n\", pc_int);\n"));
+    }
+
+    #[test]
+    #[ignore = "needs cc"]
+    fn test_c_output_matches_interpreter() {
+        let order = [
+            "x = 1 / 0 + 9223372036854775807 * 2",
+            "x = (9223372036854775807 * 2) < 1 / 0",
+            "x = 2\ny = f(x / 0, x * 9223372036854775807)",
+        ];
+        assert_output_matches_interpreter(parity_sources(&order), |index, program| {
+            let source_path = write_temp(&format!("{}.c", index), &c::generate(program));
+            let binary_path = source_path.with_extension("bin");
+            run_tool("cc", Command::new("cc").arg("-o").arg(&binary_path).arg(&source_path))
+                .expect("cc failed");
+            run_tool("program", &mut Command::new(&binary_path))
+        });
+    }
+
+    // ==================== JavaScript Tests ====================
+
+    #[test]
+    fn test_js_uses_bigint() {
+        let code = js::generate(&parse_program("var x: entero\ny = 2\nx = y * 3"));
+        assert!(code.contains("export function run() {\n    let x;\n    let y;\n"));
+        assert!(code.contains("    y = 2n;\n    x = pc_check(y * 3n);\n"));
+        assert!(code.contains("    return {\n        \"x\": x,\n        \"y\": y,\n    };\n"));
+        assert!(!code.contains("pc_div"));
+    }
+
+    #[test]
+    fn test_js_checked_division() {
+        let code = js::generate(&parse_program("x = 7 / 2"));
+        assert!(code.contains("function pc_div(a, b) {"));
+        assert!(code.contains("throw new PseudocuError(\"Division by zero\");"));
+        assert!(code.contains("    x = pc_div(7n, 2n);\n"));
+    }
+
+    #[test]
+    fn test_js_comparisons() {
+        let code = js::generate(&parse_program("x = 1 < 2 == 3"));
+        assert!(code.contains("    x = ((1n < 2n ? 1n : 0n) === 3n ? 1n : 0n);\n"));
+        assert!(!code.contains("pc_check"));
+    }
+
+    #[test]
+    fn test_js_empty_program() {
+        let code = js::generate(&parse_program(""));
+        assert!(code.contains("export function run() {\n    return {};\n}\n"));
+    }
+
+    #[test]
+    fn test_js_reserved_names() {
+        let code = js::generate(&parse_program("let = 1\nrun = let + 1\npc_x = 2"));
+        assert!(code.contains("    pc_let = 1n;\n"));
+        assert!(code.contains("    pc_run = pc_check(pc_let + 1n);\n"));
+        assert!(code.contains("    pc_pc_x = 2n;\n"));
+        assert!(code.contains("        \"run\": pc_run,\n"));
+    }
+
+    #[test]
+    #[ignore = "needs node"]
+    fn test_js_output_matches_interpreter() {
+        assert_output_matches_interpreter(parity_sources(&[]), |index, program| {
+            let path = write_temp(&format!("{}.mjs", index), &js::generate(program));
+            let driver = format!(
+                "import {{ run }} from {:?};\n\
+                 try {{\n\
+                     for (const [name, value] of Object.entries(run())) console.log(`${{name}} = ${{value}}`);\n\
+                 }} catch (error) {{\n\
+                     console.error(`Runtime error: ${{error.message}}`);\n\
+                     process.exit(1);\n\
+                 }}",
+                format!("file://{}", path.display())
+            );
+            run_tool("node", Command::new("node").args(["--input-type=module", "-e", &driver]))
+        });
+    }
+
+    // ==================== Assembly Tests ====================
+
+    #[test]
+    fn test_asm_stores_variables_in_bss() {
+        let code = asm::generate(&parse_program("x = 2\ny = x + 3"));
+        assert!(code.contains("    movabs $2, %rax\n    mov %rax, pc_var0(%rip)\n"));
+        assert!(code.contains("    add %rcx, %rax\n    jo pc_overflow\n    mov %rax, pc_var1(%rip)\n"));
+        assert!(code.contains("pc_var0:    # x\n    .zero 8\npc_var1:    # y\n    .zero 8\n"));
+        assert!(!code.contains("pc_division_by_zero"));
+    }
+
+    #[test]
+    fn test_asm_division_checks() {
+        let code = asm::generate(&parse_program("x = 7 / 2"));
+        assert!(code.contains("    test %rcx, %rcx\n    jz pc_division_by_zero\n"));
+        assert!(code.contains("    cqo\n    idiv %rcx\n"));
+        assert!(code.contains("pc_msg_division:\n    .ascii \"Runtime error: Division by zero\\n\"\n"));
+    }
+
+    #[test]
+    fn test_asm_comparisons() {
+        let code = asm::generate(&parse_program("x = 1 >= 2"));
+        assert!(code.contains("    cmp %rcx, %rax\n    setge %al\n    movzbq %al, %rax\n"));
+        assert!(!code.contains("pc_overflow"));
+    }
+
+    #[test]
+    fn test_asm_prints_sorted_variables() {
+        let code = asm::generate(&parse_program("var u: entero\nb = 1\na = 2"));
+        assert!(code.contains(".Lstr0:\n    .ascii \"a = \"\n.Lstr1:\n    .ascii \"b = \"\n"));
+        assert!(!code.contains("\"u = \""));
+    }
+
+    #[test]
+    #[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+    #[ignore = "needs as and ld"]
+    fn test_asm_output_matches_interpreter() {
+        let sources = parity_sources(&["a = 0 - 9223372036854775807 - 1\nb = a / 3"]);
+        assert_output_matches_interpreter(sources, |index, program| {
+            let source_path = write_temp(&format!("{}.s", index), &asm::generate(program));
+            let object_path = source_path.with_extension("o");
+            let binary_path = source_path.with_extension("out");
+            run_tool("as", Command::new("as").arg("-o").arg(&object_path).arg(&source_path))
+                .expect("as failed");
+            run_tool("ld", Command::new("ld").arg("-o").arg(&binary_path).arg(&object_path))
+                .expect("ld failed");
+            run_tool("program", &mut Command::new(&binary_path))
+        });
+    }
+
+    // ==================== WebAssembly Tests ====================
+
+    /// Instantiates a generated module with wasmi and calls `run`, mapping
+    /// traps back to the interpreter's error messages.
+    fn run_wat(source: &str, results: usize) -> Result<Vec<i64>, String> {
+        use wasmi::core::TrapCode;
+        use wasmi::{Engine, Linker, Module, Store, Val};
+
+        let wasm = ::wat::parse_str(source).unwrap();
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = Linker::<()>::new(&engine)
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let run = instance.get_func(&store, "run").unwrap();
+        let mut values = vec![Val::I64(0); results];
+        match run.call(&mut store, &[], &mut values) {
+            Ok(()) => Ok(values.iter().map(|value| value.i64().unwrap()).collect()),
+            Err(error) => Err(match error.as_trap_code() {
+                Some(TrapCode::IntegerDivisionByZero) => "Division by zero".to_string(),
+                Some(TrapCode::IntegerOverflow) => "Integer overflow".to_string(),
+                Some(TrapCode::UnreachableCodeReached) => "unreachable".to_string(),
+                _ => error.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_wat_globals_and_export() {
+        let code = wat::generate(&parse_program("var u: entero\nb = 1\na = b + 2"));
+        assert!(code.contains("  (global $a (mut i64) (i64.const 0))\n  (global $b (mut i64) (i64.const 0))\n  (global $u (mut i64) (i64.const 0))\n"));
+        assert!(code.contains("  ;; Returns a, b.\n  (func (export \"run\") (result i64 i64)\n"));
+        assert!(code.contains("    global.get $b\n    i64.const 2\n    call $pc_add\n    global.set $a\n"));
+        assert!(code.contains("    global.get $a\n    global.get $b\n  )\n)\n"));
+        assert!(!code.contains("$pc_mul"));
+    }
+
+    #[test]
+    fn test_wat_operators() {
+        let code = wat::generate(&parse_program("x = 7 / 2 <= 3"));
+        assert!(code.contains("    i64.div_s\n    i64.const 3\n    i64.le_s\n    i64.extend_i32_u\n"));
+        assert!(!code.contains("$pc_overflow"));
+    }
+
+    #[test]
+    fn test_wat_empty_program() {
+        let code = wat::generate(&parse_program(""));
+        assert_eq!(code, "(module\n\n  (func (export \"run\")\n  )\n)\n");
+        assert_eq!(run_wat(&code, 0), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_wat_output_matches_interpreter() {
+        let overflow: &[(&str, &[&str])] = &[
+            ("a = 9223372036854775807\nb = a + 1", &["a", "b"]),
+            ("a = 0 - 9223372036854775807 - 1\nb = a - 1", &["a", "b"]),
+            ("a = 0 - 9223372036854775807 - 1\nm = 0 - 1\nb = a / m", &["a", "b", "m"]),
+            ("a = 0 - 9223372036854775807 - 1\nm = 0 - 1\nb = m * a", &["a", "b", "m"]),
+            ("a = 4294967296\nb = a * a", &["a", "b"]),
+        ];
+        for (source, names) in PARITY_CASES.iter().chain(overflow) {
+            let program = parse_program(source);
+            let result = run_wat(&wat::generate(&program), names.len()).map(|values| {
+                let mut names = names.to_vec();
+                names.sort();
+                names.iter()
+                    .zip(values)
+                    .map(|(name, value)| format!("{} = {}\n", name, value))
+                    .collect::<String>()
+            });
+            let expected = interpreter_output(&program).map_err(|error| {
+                let unreachable = error.starts_with("Type mismatch") || error.starts_with("Undefined function");
+                if unreachable { "unreachable".to_string() } else { error }
+            });
+            assert_eq!(result, expected, "program: {}", source);
+        }
+    }
+
+    // ==================== Flowchart Tests ====================
+
+    /// A hand-built chart with a decision, as conditionals will produce.
+    fn decision_chart() -> Flowchart {
+        let mut chart = Flowchart::default();
+        let start = chart.add_node(Shape::Terminal, "Start");
+        let check = chart.add_node(Shape::Decision, "x > \"1\"");
+        let yes = chart.add_node(Shape::Process, "y = 1");
+        let end = chart.add_node(Shape::Terminal, "End");
+        chart.add_edge(start, check, None);
+        chart.add_edge(check, yes, Some("yes"));
+        chart.add_edge(check, end, Some("no"));
+        chart.add_edge(yes, end, None);
+        chart
+    }
+
+    #[test]
+    fn test_flowchart_from_program() {
+        let chart = Flowchart::from_program(&parse_program("x = 2\nvar y: entero\ny += x*3"));
+        let labels: Vec<_> = chart.nodes.iter().map(|node| (node.shape, node.label.as_str())).collect();
+        assert_eq!(labels, vec![
+            (Shape::Terminal, "Start"),
+            (Shape::Process, "x = 2"),
+            (Shape::Process, "var y: entero"),
+            (Shape::Process, "y += x * 3"),
+            (Shape::Terminal, "End"),
+        ]);
+        let edges: Vec<_> = chart.edges.iter().map(|edge| (edge.from, edge.to)).collect();
+        assert_eq!(edges, vec![(0, 1), (1, 2), (2, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn test_dot_simple_program() {
+        assert_eq!(
+            dot::generate(&parse_program("x = 2\n(x > 1)")),
+            "digraph program {\n    node [fontname=\"Helvetica\"];\n    n0 [label=\"Start\", shape=box, style=rounded];\n    n1 [label=\"x = 2\", shape=box];\n    n2 [label=\"(x > 1)\", shape=box];\n    n3 [label=\"End\", shape=box, style=rounded];\n    n0 -> n1;\n    n1 -> n2;\n    n2 -> n3;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_dot_empty_program() {
+        assert_eq!(
+            dot::generate(&parse_program("")),
+            "digraph program {\n    node [fontname=\"Helvetica\"];\n    n0 [label=\"Start\", shape=box, style=rounded];\n    n1 [label=\"End\", shape=box, style=rounded];\n    n0 -> n1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_dot_decision() {
+        let code = dot::render(&decision_chart());
+        assert!(code.contains("    n1 [label=\"x > \\\"1\\\"\", shape=diamond];\n"));
+        assert!(code.contains("    n1 -> n2 [label=\"yes\"];\n"));
+        assert!(code.contains("    n1 -> n3 [label=\"no\"];\n"));
+    }
+
+    #[test]
+    fn test_mermaid_simple_program() {
+        assert_eq!(
+            mermaid::generate(&parse_program("x = 2\n(x > 1)")),
+            "flowchart TD\n    n0([\"Start\"])\n    n1[\"x = 2\"]\n    n2[\"(x > 1)\"]\n    n3([\"End\"])\n    n0 --> n1\n    n1 --> n2\n    n2 --> n3\n"
+        );
+    }
+
+    #[test]
+    fn test_mermaid_decision() {
+        let code = mermaid::render(&decision_chart());
+        assert!(code.contains("    n1{\"x > #quot;1#quot;\"}\n"));
+        assert!(code.contains("    n1 -->|\"yes\"| n2\n"));
+        assert!(code.contains("    n1 -->|\"no\"| n3\n"));
+    }
+
+    // ==================== Call Tests ====================
+
+    #[test]
+    fn test_call_helpers_only_when_used() {
+        let program = parse_program("x = f(1)");
+        assert!(python::generate(&program).contains("    x = _pc_call(\"f\", 1)\n"));
+        assert!(c::generate(&program).contains("    x = ((void)(1LL), pc_call(\"f\"));\n"));
+        assert!(js::generate(&program).contains("    x = pc_call(\"f\", 1n);\n"));
+        let program = parse_program("x = 1");
+        assert!(!python::generate(&program).contains("_pc_call"));
+        assert!(!c::generate(&program).contains("pc_call"));
+        assert!(!js::generate(&program).contains("pc_call"));
+    }
+}
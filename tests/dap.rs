@@ -0,0 +1,353 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::path::{Path, PathBuf};
+    use pseudocu::dap;
+    use pseudocu::json::Json;
+    use pseudocu::lsp::{read_message, write_message};
+
+    const SOURCE: &str = "x = 1\ny = x + 1\n\nz = y * 10\n";
+
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pseudocu-dap-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn request(command: &str, arguments: Json) -> Json {
+        Json::object([
+            ("seq", Json::Int(0)),
+            ("type", Json::string("request")),
+            ("command", Json::string(command)),
+            ("arguments", arguments),
+        ])
+    }
+
+    fn launch(path: &Path, stop_on_entry: bool) -> Json {
+        request("launch", Json::object([
+            ("program", Json::string(path.to_str().unwrap())),
+            ("stopOnEntry", Json::Bool(stop_on_entry)),
+        ]))
+    }
+
+    fn set_breakpoints(path: &Path, lines: &[i64]) -> Json {
+        let breakpoints = lines.iter().map(|line| Json::object([("line", Json::Int(*line))])).collect();
+        request("setBreakpoints", Json::object([
+            ("source", Json::object([("path", Json::string(path.to_str().unwrap()))])),
+            ("breakpoints", Json::Array(breakpoints)),
+        ]))
+    }
+
+    fn simple(command: &str) -> Json {
+        request(command, Json::object([("threadId", Json::Int(1))]))
+    }
+
+    fn evaluate(expression: &str) -> Json {
+        request("evaluate", Json::object([("expression", Json::string(expression))]))
+    }
+
+    /// Runs a session fed with `requests` and returns every message sent back.
+    fn session(requests: &[Json]) -> Vec<Json> {
+        let mut input = Vec::new();
+        for (seq, message) in requests.iter().enumerate() {
+            let Json::Object(mut members) = message.clone() else { unreachable!() };
+            members[0].1 = Json::Int(seq as i64 + 1);
+            write_message(&mut input, &Json::Object(members)).unwrap();
+        }
+        let mut output = Vec::new();
+        dap::run(Cursor::new(input), &mut output).unwrap();
+
+        let mut reader = Cursor::new(output);
+        let mut messages = Vec::new();
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    fn events<'a>(messages: &'a [Json], name: &str) -> Vec<&'a Json> {
+        messages
+            .iter()
+            .filter(|message| message.get("event").and_then(Json::as_str) == Some(name))
+            .collect()
+    }
+
+    fn response<'a>(messages: &'a [Json], command: &str) -> Vec<&'a Json> {
+        messages
+            .iter()
+            .filter(|message| message.get("command").and_then(Json::as_str) == Some(command))
+            .collect()
+    }
+
+    fn stopped_lines(messages: &[Json]) -> Vec<i64> {
+        response(messages, "stackTrace")
+            .iter()
+            .filter_map(|reply| reply.pointer(&["body", "stackFrames"])?.as_array()?.first()?.get("line")?.as_i64())
+            .collect()
+    }
+
+    fn output(messages: &[Json]) -> String {
+        events(messages, "output")
+            .iter()
+            .filter_map(|event| event.pointer(&["body", "output"])?.as_str())
+            .collect()
+    }
+
+    // ==================== Lifecycle Tests ====================
+
+    #[test]
+    fn test_initialize_sends_capabilities_and_initialized() {
+        let messages = session(&[request("initialize", Json::object([])), simple("disconnect")]);
+        assert_eq!(messages[0].get("type").and_then(Json::as_str), Some("response"));
+        assert_eq!(messages[0].get("request_seq").and_then(Json::as_i64), Some(1));
+        assert_eq!(
+            messages[0].pointer(&["body", "supportsConfigurationDoneRequest"]),
+            Some(&Json::Bool(true))
+        );
+        assert_eq!(messages[1].get("event").and_then(Json::as_str), Some("initialized"));
+        let seqs: Vec<_> = messages.iter().filter_map(|message| message.get("seq")?.as_i64()).collect();
+        assert_eq!(seqs, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_run_to_completion_without_breakpoints() {
+        let path = write_temp("complete.pc", SOURCE);
+        let messages = session(&[
+            request("initialize", Json::object([])),
+            launch(&path, false),
+            request("configurationDone", Json::object([])),
+            simple("disconnect"),
+        ]);
+        assert!(events(&messages, "stopped").is_empty());
+        assert_eq!(output(&messages), "x = 1\ny = 2\nz = 20\n");
+        assert_eq!(
+            events(&messages, "exited")[0].pointer(&["body", "exitCode"]),
+            Some(&Json::Int(0))
+        );
+        assert_eq!(events(&messages, "terminated").len(), 1);
+        assert_eq!(response(&messages, "disconnect").len(), 1);
+    }
+
+    #[test]
+    fn test_launch_reports_errors() {
+        let path = write_temp("broken.pc", "x = = 1\n");
+        let messages = session(&[launch(&path, false), simple("disconnect")]);
+        let reply = response(&messages, "launch")[0];
+        assert_eq!(reply.get("success"), Some(&Json::Bool(false)));
+        assert!(reply.get("message").and_then(Json::as_str).unwrap().starts_with("Parser error: "));
+
+        let messages = session(&[request("launch", Json::object([])), simple("disconnect")]);
+        assert_eq!(
+            response(&messages, "launch")[0].get("message").and_then(Json::as_str),
+            Some("Missing program to launch")
+        );
+    }
+
+    #[test]
+    fn test_runtime_error_is_reported() {
+        let path = write_temp("error.pc", "x = 1\ny = x / 0\n");
+        let messages = session(&[launch(&path, false), request("configurationDone", Json::object([]))]);
+        assert_eq!(output(&messages), "Runtime error: Division by zero\n");
+        assert_eq!(
+            events(&messages, "exited")[0].pointer(&["body", "exitCode"]),
+            Some(&Json::Int(1))
+        );
+    }
+
+    #[test]
+    fn test_unknown_request_fails() {
+        let messages = session(&[request("restartFrame", Json::object([]))]);
+        assert_eq!(messages[0].get("success"), Some(&Json::Bool(false)));
+        assert_eq!(
+            messages[0].get("message").and_then(Json::as_str),
+            Some("Unsupported request: restartFrame")
+        );
+    }
+
+    #[test]
+    fn test_malformed_messages_are_reported_and_skipped() {
+        let mut input = b"Content-Length: 5\r\n\r\n{bad}".to_vec();
+        write_message(&mut input, &request("initialize", Json::object([]))).unwrap();
+        // The body of an oversized message is skipped, which here is the rest
+        // of the input.
+        input.extend(format!("Content-Length: {}\r\n\r\n", pseudocu::lsp::MAX_CONTENT_LENGTH + 1).bytes());
+        let mut replies = Vec::new();
+        dap::run(Cursor::new(input), &mut replies).unwrap();
+
+        let mut reader = Cursor::new(replies);
+        let mut messages = Vec::new();
+        while let Some(message) = read_message(&mut reader).unwrap() {
+            messages.push(message);
+        }
+        assert_eq!(
+            output(&messages),
+            "Invalid message: Expected string key at offset 1\n\
+             Invalid message: Content-Length 16777217 exceeds the limit of 16777216 bytes\n"
+        );
+        assert_eq!(response(&messages, "initialize")[0].get("success"), Some(&Json::Bool(true)));
+    }
+
+    // ==================== Stepping Tests ====================
+
+    #[test]
+    fn test_stop_on_entry_and_step() {
+        let path = write_temp("step.pc", SOURCE);
+        let messages = session(&[
+            launch(&path, true),
+            request("configurationDone", Json::object([])),
+            simple("stackTrace"),
+            simple("next"),
+            simple("stackTrace"),
+            simple("stepIn"),
+            simple("stackTrace"),
+            simple("continue"),
+        ]);
+        let reasons: Vec<_> = events(&messages, "stopped")
+            .iter()
+            .filter_map(|event| event.pointer(&["body", "reason"])?.as_str())
+            .collect();
+        assert_eq!(reasons, vec!["entry", "step", "step"]);
+        assert_eq!(stopped_lines(&messages), vec![1, 2, 4]);
+        assert_eq!(output(&messages), "x = 1\ny = 2\nz = 20\n");
+    }
+
+    #[test]
+    fn test_next_skips_statements_on_the_same_line() {
+        let path = write_temp("same-line.pc", "const a = 1 b = 2\nc = a + b\n");
+        let messages = session(&[
+            launch(&path, true),
+            request("configurationDone", Json::object([])),
+            simple("next"),
+            simple("stackTrace"),
+            simple("continue"),
+        ]);
+        assert_eq!(events(&messages, "stopped").len(), 2);
+        assert_eq!(stopped_lines(&messages), vec![2]);
+    }
+
+    #[test]
+    fn test_disconnect_while_paused_stops_the_program() {
+        let path = write_temp("disconnect.pc", SOURCE);
+        let messages = session(&[
+            launch(&path, true),
+            request("configurationDone", Json::object([])),
+            simple("disconnect"),
+        ]);
+        assert_eq!(response(&messages, "disconnect").len(), 1);
+        assert!(events(&messages, "exited").is_empty());
+        assert!(output(&messages).is_empty());
+    }
+
+    // ==================== Breakpoint Tests ====================
+
+    #[test]
+    fn test_breakpoints_are_verified_against_statements() {
+        let path = write_temp("verify.pc", SOURCE);
+        let messages = session(&[
+            launch(&path, false),
+            set_breakpoints(&path, &[2, 3]),
+            simple("disconnect"),
+        ]);
+        let breakpoints = response(&messages, "setBreakpoints")[0]
+            .pointer(&["body", "breakpoints"])
+            .and_then(Json::as_array)
+            .unwrap();
+        assert_eq!(breakpoints[0].get("verified"), Some(&Json::Bool(true)));
+        assert_eq!(breakpoints[0].get("line"), Some(&Json::Int(2)));
+        assert_eq!(breakpoints[1].get("verified"), Some(&Json::Bool(false)));
+        assert_eq!(
+            breakpoints[1].get("message").and_then(Json::as_str),
+            Some("No statement on this line")
+        );
+    }
+
+    #[test]
+    fn test_continue_stops_at_breakpoints() {
+        let path = write_temp("breakpoints.pc", SOURCE);
+        let messages = session(&[
+            request("initialize", Json::object([])),
+            set_breakpoints(&path, &[2, 4]),
+            launch(&path, false),
+            request("configurationDone", Json::object([])),
+            simple("stackTrace"),
+            simple("continue"),
+            simple("stackTrace"),
+            simple("continue"),
+        ]);
+        let reasons: Vec<_> = events(&messages, "stopped")
+            .iter()
+            .filter_map(|event| event.pointer(&["body", "reason"])?.as_str())
+            .collect();
+        assert_eq!(reasons, vec!["breakpoint", "breakpoint"]);
+        assert_eq!(stopped_lines(&messages), vec![2, 4]);
+        assert_eq!(events(&messages, "terminated").len(), 1);
+    }
+
+    #[test]
+    fn test_breakpoints_can_change_while_paused() {
+        let path = write_temp("change.pc", SOURCE);
+        let messages = session(&[
+            set_breakpoints(&path, &[1]),
+            launch(&path, false),
+            request("configurationDone", Json::object([])),
+            set_breakpoints(&path, &[4]),
+            simple("continue"),
+            simple("stackTrace"),
+            set_breakpoints(&path, &[]),
+            simple("continue"),
+        ]);
+        assert_eq!(stopped_lines(&messages), vec![4]);
+        assert_eq!(events(&messages, "stopped").len(), 2);
+    }
+
+    // ==================== Inspection Tests ====================
+
+    #[test]
+    fn test_variables_pane_shows_interpreter_variables() {
+        let path = write_temp("variables.pc", SOURCE);
+        let messages = session(&[
+            set_breakpoints(&path, &[4]),
+            launch(&path, false),
+            request("configurationDone", Json::object([])),
+            simple("threads"),
+            request("scopes", Json::object([("frameId", Json::Int(1))])),
+            request("variables", Json::object([("variablesReference", Json::Int(1))])),
+            simple("continue"),
+        ]);
+        let threads = response(&messages, "threads")[0].pointer(&["body", "threads"]).unwrap();
+        assert_eq!(threads.to_string(), r#"[{"id":1,"name":"main"}]"#);
+        let scope = &response(&messages, "scopes")[0]
+            .pointer(&["body", "scopes"])
+            .and_then(Json::as_array)
+            .unwrap()[0];
+        assert_eq!(scope.get("variablesReference"), Some(&Json::Int(1)));
+        let variables = response(&messages, "variables")[0].pointer(&["body", "variables"]).unwrap();
+        assert_eq!(
+            variables.to_string(),
+            r#"[{"name":"x","value":"1","variablesReference":0},{"name":"y","value":"2","variablesReference":0}]"#
+        );
+    }
+
+    #[test]
+    fn test_evaluate_expressions() {
+        let path = write_temp("evaluate.pc", SOURCE);
+        let messages = session(&[
+            evaluate("1 + 1"),
+            launch(&path, true),
+            request("configurationDone", Json::object([])),
+            simple("next"),
+            evaluate("(x + 1) * 3"),
+            evaluate("y"),
+            evaluate("1 2"),
+            simple("continue"),
+        ]);
+        let replies = response(&messages, "evaluate");
+        assert_eq!(replies[0].get("message").and_then(Json::as_str), Some("The program is not paused"));
+        assert_eq!(replies[1].pointer(&["body", "result"]).and_then(Json::as_str), Some("6"));
+        assert_eq!(replies[2].get("message").and_then(Json::as_str), Some("Undefined variable: y"));
+        assert_eq!(
+            replies[3].get("message").and_then(Json::as_str),
+            Some("Unexpected input after expression: 1 2")
+        );
+    }
+}
@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use pseudocu::debugger::Debugger;
+    use pseudocu::interpreter::Interpreter;
+    use pseudocu::lexer::tokenize;
+    use pseudocu::parser::Parser;
+
+    const SOURCE: &str = "x = 1\ny = x + 1\nz = y * 10\nw = z - 5\n";
+
+    /// Runs `source` under the debugger with `commands` as its input and
+    /// returns the debugger's output and the final interpreter.
+    fn debug(source: &str, commands: &str) -> (String, Interpreter, bool) {
+        let tokens = tokenize(source.to_string()).unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        let mut output = Vec::new();
+        let mut debugger = Debugger::new(source, Cursor::new(commands.as_bytes()), &mut output);
+        interpreter.run_with_hook(&program, &mut debugger).unwrap();
+        let quit = debugger.quit_requested();
+        (String::from_utf8(output).unwrap(), interpreter, quit)
+    }
+
+    fn pauses(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .map(|line| line.trim_start_matches("(pcdb) "))
+            .filter(|line| line.starts_with("Paused at"))
+            .collect()
+    }
+
+    // ==================== Stepping Tests ====================
+
+    #[test]
+    fn test_pauses_before_first_statement() {
+        let (output, interpreter, quit) = debug(SOURCE, "c\n");
+        assert_eq!(pauses(&output), vec!["Paused at line 1: x = 1"]);
+        assert_eq!(interpreter.get_variable("w"), Some(15));
+        assert!(!quit);
+    }
+
+    #[test]
+    fn test_step_pauses_at_every_statement() {
+        let (output, _, _) = debug(SOURCE, "s\ns\nstep\nc\n");
+        assert_eq!(pauses(&output), vec![
+            "Paused at line 1: x = 1",
+            "Paused at line 2: y = x + 1",
+            "Paused at line 3: z = y * 10",
+            "Paused at line 4: w = z - 5",
+        ]);
+    }
+
+    #[test]
+    fn test_next_skips_statements_on_the_same_line() {
+        let source = "const a = 1 b = 2\nc = a + b\n";
+        let (output, _, _) = debug(source, "n\nc\n");
+        assert_eq!(pauses(&output), vec![
+            "Paused at line 1: const a = 1 b = 2",
+            "Paused at line 2: c = a + b",
+        ]);
+
+        let (output, _, _) = debug(source, "s\ns\nc\n");
+        assert_eq!(pauses(&output).len(), 3);
+    }
+
+    #[test]
+    fn test_end_of_input_runs_to_completion() {
+        let (output, interpreter, quit) = debug(SOURCE, "b 3\n");
+        assert_eq!(pauses(&output).len(), 1);
+        assert_eq!(interpreter.get_variable("w"), Some(15));
+        assert!(!quit);
+    }
+
+    #[test]
+    fn test_quit_stops_the_program() {
+        let (_, interpreter, quit) = debug(SOURCE, "s\nq\n");
+        assert_eq!(interpreter.get_variable("x"), Some(1));
+        assert_eq!(interpreter.get_variable("y"), None);
+        assert!(quit);
+    }
+
+    // ==================== Breakpoint Tests ====================
+
+    #[test]
+    fn test_continue_stops_at_breakpoints() {
+        let (output, _, _) = debug(SOURCE, "b 2\nbreak 4\nc\nc\nc\n");
+        assert!(output.contains("Breakpoint at line 2\n"));
+        assert_eq!(pauses(&output), vec![
+            "Paused at line 1: x = 1",
+            "Paused at line 2: y = x + 1",
+            "Paused at line 4: w = z - 5",
+        ]);
+    }
+
+    #[test]
+    fn test_break_rejects_lines_without_statements() {
+        let (output, _, _) = debug("x = 1\n\ny = 2\n", "b 2\nb two\nc\n");
+        assert!(output.contains("No statement at line 2\n"));
+        assert!(output.contains("Invalid line number: two\n"));
+        assert_eq!(pauses(&output).len(), 1);
+    }
+
+    #[test]
+    fn test_list_and_delete_breakpoints() {
+        let (output, _, _) = debug(SOURCE, "b\nb 3\nb 2\nb\nd 3\nd 3\nc\nc\n");
+        assert!(output.contains("No breakpoints\n"));
+        assert!(output.contains("Breakpoint at line 2\n(pcdb) Breakpoint at line 2\nBreakpoint at line 3\n"));
+        assert!(output.contains("Deleted breakpoint at line 3\n"));
+        assert!(output.contains("No breakpoint at line 3\n"));
+        assert_eq!(pauses(&output), vec![
+            "Paused at line 1: x = 1",
+            "Paused at line 2: y = x + 1",
+        ]);
+    }
+
+    #[test]
+    fn test_breakpoint_set_before_run() {
+        let tokens = tokenize(SOURCE.to_string()).unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut output = Vec::new();
+        let mut debugger = Debugger::new(SOURCE, Cursor::new("c\nc\n"), &mut output);
+        debugger.add_breakpoint(3);
+        Interpreter::new().run_with_hook(&program, &mut debugger).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Paused at line 3: z = y * 10\n"));
+    }
+
+    // ==================== Inspection Tests ====================
+
+    #[test]
+    fn test_print_expression() {
+        let (output, _, _) = debug(SOURCE, "s\ns\np x + 41\nprint (x + 1) * 3\nc\n");
+        assert!(output.contains("x + 41 = 42\n"));
+        assert!(output.contains("(x + 1) * 3 = 6\n"));
+    }
+
+    #[test]
+    fn test_print_reports_errors() {
+        let (output, _, _) = debug(SOURCE, "p y\np 1 +\np 1 2\np\nc\n");
+        assert!(output.contains("y = <Undefined variable: y>\n"));
+        assert!(output.contains("Error: Expected number or identifier, found: EOF at line 1\n"));
+        assert!(output.contains("Error: Unexpected input after expression: 1 2\n"));
+        assert!(output.contains("Error: Expected an expression\n"));
+    }
+
+    #[test]
+    fn test_watch_shows_values_at_each_pause() {
+        let (output, _, _) = debug(SOURCE, "w y * 2\ns\ns\nunwatch y * 2\ns\nc\n");
+        assert!(output.contains("Watching y * 2 = <Undefined variable: y>\n"));
+        assert!(output.contains("Paused at line 2: y = x + 1\n  y * 2 = <Undefined variable: y>\n"));
+        assert!(output.contains("Paused at line 3: z = y * 10\n  y * 2 = 4\n"));
+        assert!(output.contains("Paused at line 4: w = z - 5\n(pcdb) "));
+    }
+
+    #[test]
+    fn test_vars_lists_initialized_variables() {
+        let (output, _, _) = debug(SOURCE, "v\ns\ns\nvars\nc\n");
+        assert!(output.contains("No variables\n"));
+        assert!(output.contains("x = 1\ny = 2\n"));
+    }
+
+    #[test]
+    fn test_list_shows_context() {
+        let (output, _, _) = debug(SOURCE, "s\nl\nc\n");
+        assert!(output.contains("     1 x = 1\n->   2 y = x + 1\n     3 z = y * 10\n     4 w = z - 5\n"));
+    }
+
+    #[test]
+    fn test_help_and_unknown_commands() {
+        let (output, _, _) = debug(SOURCE, "h\nfoo\nc\n");
+        assert!(output.contains("Commands:\n"));
+        assert!(output.contains("Unknown command: foo (type 'help' for a list)\n"));
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Operator, Program, Statement};
+    use pseudocu::dump::{ast_json, ast_tree, tokens};
+    use pseudocu::json::Json;
+    use pseudocu::lexer::tokenize;
+    use pseudocu::parser::Parser;
+
+    fn parse(source: &str) -> Program {
+        Parser::new(tokenize(source.to_string()).unwrap()).parse().unwrap()
+    }
+
+    // ==================== Tree Tests ====================
+
+    #[test]
+    fn test_tree_nests_expressions() {
+        assert_eq!(
+            ast_tree(&parse("y += x * (3 - 1)\n(x > y)")),
+            "Program\n  CompoundAssign y Plus (line 1)\n    BinaryOp Multiply\n      Identifier x\n      BinaryOp Minus\n        Number 3\n        Number 1\n  Expr (line 2)\n    BinaryOp Gt\n      Identifier x\n      Identifier y\n"
+        );
+    }
+
+    #[test]
+    fn test_tree_declarations() {
+        assert_eq!(
+            ast_tree(&parse("const K = 1\nvar a: booleano\nvar b = K")),
+            "Program\n  Const K (line 1)\n    Number 1\n  Declare a: booleano (line 2)\n  Declare b (line 3)\n    Identifier K\n"
+        );
+    }
+
+    #[test]
+    fn test_tree_without_lines() {
+        let program = Program {
+            statements: vec![Statement::Assign { name: "x".to_string(), value: Expr::Number(-4) }],
+            lines: Vec::new(),
+        };
+        assert_eq!(ast_tree(&program), "Program\n  Assign x\n    Number -4\n");
+        assert_eq!(ast_tree(&parse("")), "Program\n");
+    }
+
+    // ==================== JSON Tests ====================
+
+    #[test]
+    fn test_json_statements() {
+        let json = ast_json(&parse("x = 2\nvar y: entero\nvar z\nconst K = x\n(x > 1)"));
+        assert_eq!(
+            json.to_string(),
+            concat!(
+                r#"{"version":1,"statements":["#,
+                r#"{"kind":"Assign","line":1,"name":"x","value":{"kind":"Number","value":2}},"#,
+                r#"{"kind":"Declare","line":2,"name":"y","type":"entero","value":null},"#,
+                r#"{"kind":"Declare","line":3,"name":"z","type":null,"value":null},"#,
+                r#"{"kind":"Const","line":4,"name":"K","value":{"kind":"Identifier","name":"x"}},"#,
+                r#"{"kind":"Expr","line":5,"expr":{"kind":"BinaryOp","operator":"Gt","#,
+                r#""left":{"kind":"Identifier","name":"x"},"right":{"kind":"Number","value":1}}}"#,
+                r#"]}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_operators() {
+        let source = "a = 1 + 2 - 3 * 4 / 5\nb = (1 == 2) != (3 < 4)\nc = (1 <= 2) >= 3\nc -= 1\nc *= 2\nc /= 3";
+        let json = ast_json(&parse(source)).to_string();
+        for name in ["Plus", "Minus", "Multiply", "Divide", "Eq", "NotEq", "Lt", "LtEq", "GtEq"] {
+            assert!(json.contains(&format!("\"operator\":\"{}\"", name)), "missing {}", name);
+        }
+        let program = Program {
+            statements: vec![Statement::CompoundAssign {
+                name: "x".to_string(),
+                operator: Operator::Gt,
+                value: Expr::Number(1),
+            }],
+            lines: Vec::new(),
+        };
+        assert_eq!(
+            ast_json(&program).to_string(),
+            r#"{"version":1,"statements":[{"kind":"CompoundAssign","line":null,"name":"x","operator":"Gt","value":{"kind":"Number","value":1}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_json_parses_back() {
+        let json = ast_json(&parse("x = 9223372036854775807"));
+        let parsed = Json::parse(&json.to_string()).unwrap();
+        assert_eq!(parsed, json);
+        let statement = &parsed.get("statements").and_then(Json::as_array).unwrap()[0];
+        assert_eq!(
+            statement.pointer(&["value", "value"]).and_then(Json::as_i64),
+            Some(i64::MAX)
+        );
+    }
+
+    // ==================== Token Tests ====================
+
+    #[test]
+    fn test_dump_tokens() {
+        let lexed = tokenize("var x: entero\nx += 10 # ten\n".to_string()).unwrap();
+        assert_eq!(
+            tokens(&lexed),
+            "   1  Var              \"var\"\n   1  Ident(\"x\")       \"x\"\n   1  Colon            \":\"\n   1  Ident(\"entero\")  \"entero\"\n   2  Ident(\"x\")       \"x\"\n   2  PlusAssign       \"+=\"\n   2  Number(10)       \"10\"\n   3  EOF              \"\"\n"
+        );
+    }
+
+    #[test]
+    fn test_dump_tokens_unknown_character() {
+        let dump = tokens(&tokenize("x = @".to_string()).unwrap());
+        assert!(dump.ends_with("   1  EOF              \"@\"\n"));
+    }
+
+    // ==================== Call Tests ====================
+
+    #[test]
+    fn test_dump_call() {
+        let program = parse("f(x, 1)");
+        assert_eq!(ast_tree(&program), "Program\n  Expr (line 1)\n    Call f\n      Identifier x\n      Number 1\n");
+        assert_eq!(
+            ast_json(&program).to_string(),
+            r#"{"version":1,"statements":[{"kind":"Expr","line":1,"expr":{"kind":"Call","name":"f","args":[{"kind":"Identifier","name":"x"},{"kind":"Number","value":1}]}}]}"#
+        );
+    }
+}
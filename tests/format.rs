@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Operator, Program, Statement};
+    use pseudocu::format::{format_expr, format_program, format_source, format_statement};
+    use pseudocu::lexer::tokenize;
+    use pseudocu::parser::Parser;
+
+    fn parse_program(input: &str) -> Program {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse().unwrap()
+    }
+
+    fn binary(left: Expr, operator: Operator, right: Expr) -> Expr {
+        Expr::BinaryOp {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+        }
+    }
+
+    fn ident(name: &str) -> Expr {
+        Expr::Identifier(name.to_string())
+    }
+
+    /// Parses, prints and parses again, checking that the tree survives.
+    fn assert_round_trip(source: &str) {
+        let program = parse_program(source);
+        let printed = format_program(&program);
+        let reparsed = parse_program(&printed);
+        assert_eq!(reparsed.statements, program.statements, "printed: {}", printed);
+        assert_eq!(format_program(&reparsed), printed);
+    }
+
+    // ==================== Spacing Tests ====================
+
+    #[test]
+    fn test_spacing_around_operators() {
+        assert_eq!(format_source("x=1+2*3").unwrap(), "x = 1 + 2 * 3\n");
+        assert_eq!(format_source("y   +=x>=2").unwrap(), "y += x >= 2\n");
+    }
+
+    #[test]
+    fn test_declarations() {
+        assert_eq!(format_source("const  K=3").unwrap(), "const K = 3\n");
+        assert_eq!(format_source("var x :entero").unwrap(), "var x: entero\n");
+        assert_eq!(format_source("var f:booleano=1").unwrap(), "var f: booleano = 1\n");
+        assert_eq!(format_source("var y = 2").unwrap(), "var y = 2\n");
+    }
+
+    #[test]
+    fn test_one_statement_per_line() {
+        assert_eq!(format_source("x = 1 y = 2\n\n\n\nz = 3").unwrap(), "x = 1\ny = 2\n\nz = 3\n");
+    }
+
+    #[test]
+    fn test_empty_source() {
+        assert_eq!(format_source("").unwrap(), "");
+        assert_eq!(format_source("\n\n").unwrap(), "");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(format_source("x = (1 +").is_err());
+    }
+
+    // ==================== Parentheses Tests ====================
+
+    #[test]
+    fn test_redundant_parentheses_removed() {
+        assert_eq!(format_source("x = (1 * 2) + (3)").unwrap(), "x = 1 * 2 + 3\n");
+        assert_eq!(format_source("x = ((a - b)) - c").unwrap(), "x = a - b - c\n");
+    }
+
+    #[test]
+    fn test_required_parentheses_kept() {
+        assert_eq!(format_source("x = (1 + 2) * 3").unwrap(), "x = (1 + 2) * 3\n");
+        assert_eq!(format_source("x = a - (b - c)").unwrap(), "x = a - (b - c)\n");
+        assert_eq!(format_source("x = a / (b * c)").unwrap(), "x = a / (b * c)\n");
+        assert_eq!(format_source("x = a == (b < c)").unwrap(), "x = a == (b < c)\n");
+    }
+
+    #[test]
+    fn test_expression_statement_starting_with_identifier() {
+        assert_eq!(format_source("(x) > 1").unwrap(), "(x > 1)\n");
+        assert_eq!(format_source("1 + x == 2").unwrap(), "1 + x == 2\n");
+        assert_eq!(format_source("x").unwrap(), "x\n");
+    }
+
+    #[test]
+    fn test_negative_numbers() {
+        assert_eq!(format_expr(&binary(ident("x"), Operator::Multiply, Expr::Number(-5))), "x * (0 - 5)");
+        assert_eq!(format_expr(&Expr::Number(i64::MIN)), "(0 - 9223372036854775807 - 1)");
+    }
+
+    #[test]
+    fn test_format_statement() {
+        let statement = Statement::CompoundAssign {
+            name: "x".to_string(),
+            operator: Operator::Divide,
+            value: binary(Expr::Number(1), Operator::Plus, Expr::Number(2)),
+        };
+        assert_eq!(format_statement(&statement), "x /= 1 + 2");
+    }
+
+    // ==================== Comment Tests ====================
+
+    #[test]
+    fn test_comments_preserved() {
+        let source = "# header\n\nx=1   # one\n  # about y\ny=x*2\n# end\n";
+        assert_eq!(
+            format_source(source).unwrap(),
+            "# header\n\nx = 1  # one\n# about y\ny = x * 2\n# end\n"
+        );
+    }
+
+    #[test]
+    fn test_trailing_comment_on_multiline_statement() {
+        let source = "x = 1 +\n  2 # sum\ny = 3";
+        assert_eq!(format_source(source).unwrap(), "x = 1 + 2  # sum\ny = 3\n");
+    }
+
+    #[test]
+    fn test_only_comments() {
+        assert_eq!(format_source("#a\n\n\n#b").unwrap(), "#a\n\n#b\n");
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let source = "# c\nconst K=3 # k\n\n\nvar f:booleano\nx=(K+1)*(K-1)\n(x)<=K\n#tail";
+        let once = format_source(source).unwrap();
+        assert_eq!(format_source(&once).unwrap(), once);
+    }
+
+    // ==================== Round Trip Tests ====================
+
+    #[test]
+    fn test_round_trip_arithmetic() {
+        assert_round_trip("x = 1 + 2 * 3 - 4 / 5");
+        assert_round_trip("x = (1 + 2) * (3 - 4) / (5 / 6)");
+        assert_round_trip("x = a - (b + c) - (d - e)");
+    }
+
+    #[test]
+    fn test_round_trip_comparisons() {
+        assert_round_trip("x = a < b == (c >= d)");
+        assert_round_trip("x = 1 + 2 != 3 * 4");
+        assert_round_trip("(x) <= (y > z)");
+    }
+
+    #[test]
+    fn test_round_trip_statements() {
+        assert_round_trip("const K = 2\nvar x: entero\nvar f: booleano = 1\nx = K\nx *= K + 1\nx -= 1\nx\n3");
+    }
+
+    #[test]
+    fn test_round_trip_built_tree() {
+        let program = Program {
+            statements: vec![Statement::Expr(binary(
+                binary(ident("a"), Operator::Gt, ident("b")),
+                Operator::Eq,
+                binary(ident("c"), Operator::Divide, binary(ident("d"), Operator::Multiply, Expr::Number(2))),
+            ))],
+            lines: Vec::new(),
+        };
+        let printed = format_program(&program);
+        assert_eq!(printed, "(a > b == c / (d * 2))\n");
+        assert_eq!(parse_program(&printed).statements, program.statements);
+    }
+
+    // ==================== Call Tests ====================
+
+    #[test]
+    fn test_calls() {
+        assert_eq!(format_source("x=max( a,2*3 )+f()").unwrap(), "x = max(a, 2 * 3) + f()\n");
+        assert_round_trip("show(x)\nf(x, g(1)) * 2\ny = (f(1) > 2) == 1");
+    }
+
+    // ==================== Error Tests ====================
+
+    #[test]
+    fn test_unexpected_character_is_rejected() {
+        assert_eq!(
+            format_source("x = 1\ny = 2 @ 3\nz = 4").unwrap_err(),
+            "Unexpected character: @ at line 2"
+        );
+    }
+}
@@ -1,12 +1,20 @@
 #[cfg(test)]
 mod tests {
-    use pseudocu::ast::{Expr, Operator, Program, Statement};
-    use pseudocu::interpreter::Interpreter;
+    use std::io::Write;
+    use std::ops::ControlFlow;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use pseudocu::ast::{Expr, Operator, Program, Statement, Type};
+    use pseudocu::interpreter::{ExecutionHook, Interpreter, Limits};
+    use pseudocu::lexer::tokenize;
+    use pseudocu::output::SharedBuffer;
+    use pseudocu::parser::Parser;
+    use pseudocu::vm::Vm;
 
     fn run_program(statements: Vec<Statement>) -> Interpreter {
-        let program = Program { statements };
+        let program = Program { statements, lines: Vec::new() };
         let mut interpreter = Interpreter::new();
-        interpreter.run(program).unwrap();
+        interpreter.run(&program).unwrap();
         interpreter
     }
 
@@ -119,9 +127,10 @@ mod tests {
                     },
                 }
             ],
+            lines: Vec::new(),
         };
         let mut interpreter = Interpreter::new();
-        let result = interpreter.run(program);
+        let result = interpreter.run(&program);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "Division by zero");
     }
@@ -233,9 +242,10 @@ mod tests {
                     },
                 }
             ],
+            lines: Vec::new(),
         };
         let mut interpreter = Interpreter::new();
-        let result = interpreter.run(program);
+        let result = interpreter.run(&program);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Undefined variable"));
     }
@@ -540,4 +550,920 @@ mod tests {
         ]);
         assert_eq!(interpreter.get_variable("result"), Some(26));
     }
+
+    // ==================== Compound Assignment Tests ====================
+
+    #[test]
+    fn test_compound_assignment_operators() {
+        let interpreter = run_program(vec![
+            Statement::Assign {
+                name: "a".to_string(),
+                value: Expr::Number(10),
+            },
+            Statement::CompoundAssign {
+                name: "a".to_string(),
+                operator: Operator::Plus,
+                value: Expr::Number(5),
+            },
+            Statement::CompoundAssign {
+                name: "a".to_string(),
+                operator: Operator::Minus,
+                value: Expr::Number(3),
+            },
+            Statement::CompoundAssign {
+                name: "a".to_string(),
+                operator: Operator::Multiply,
+                value: Expr::Number(4),
+            },
+            Statement::CompoundAssign {
+                name: "a".to_string(),
+                operator: Operator::Divide,
+                value: Expr::Number(6),
+            },
+        ]);
+        // ((10 + 5 - 3) * 4) / 6 = 8
+        assert_eq!(interpreter.get_variable("a"), Some(8));
+    }
+
+    #[test]
+    fn test_compound_assignment_with_variable() {
+        let interpreter = run_program(vec![
+            Statement::Assign {
+                name: "total".to_string(),
+                value: Expr::Number(1),
+            },
+            Statement::Assign {
+                name: "x".to_string(),
+                value: Expr::Number(41),
+            },
+            Statement::CompoundAssign {
+                name: "total".to_string(),
+                operator: Operator::Plus,
+                value: Expr::Identifier("x".to_string()),
+            },
+        ]);
+        assert_eq!(interpreter.get_variable("total"), Some(42));
+    }
+
+    #[test]
+    fn test_compound_assignment_undefined_variable_error() {
+        let program = Program {
+            statements: vec![
+                Statement::CompoundAssign {
+                    name: "total".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Number(1),
+                }
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Undefined variable: total".to_string()));
+        assert_eq!(interpreter.get_variable("total"), None);
+    }
+
+    #[test]
+    fn test_compound_assignment_division_by_zero_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "x".to_string(),
+                    value: Expr::Number(10),
+                },
+                Statement::CompoundAssign {
+                    name: "x".to_string(),
+                    operator: Operator::Divide,
+                    value: Expr::Number(0),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Division by zero".to_string()));
+        assert_eq!(interpreter.get_variable("x"), Some(10));
+    }
+
+    // ==================== Constant Tests ====================
+
+    #[test]
+    fn test_const_declaration() {
+        let interpreter = run_program(vec![
+            Statement::Const {
+                name: "PI_APPROX".to_string(),
+                value: Expr::Number(314),
+            },
+            Statement::Assign {
+                name: "double".to_string(),
+                value: Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("PI_APPROX".to_string())),
+                    operator: Operator::Multiply,
+                    right: Box::new(Expr::Number(2)),
+                },
+            },
+        ]);
+        assert_eq!(interpreter.get_variable("PI_APPROX"), Some(314));
+        assert_eq!(interpreter.get_variable("double"), Some(628));
+        assert!(interpreter.is_constant("PI_APPROX"));
+        assert!(!interpreter.is_constant("double"));
+    }
+
+    #[test]
+    fn test_assign_to_constant_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Const {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(10),
+                },
+                Statement::Assign {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(20),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Cannot assign to constant: MAX".to_string()));
+        assert_eq!(interpreter.get_variable("MAX"), Some(10));
+    }
+
+    #[test]
+    fn test_compound_assign_to_constant_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Const {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(10),
+                },
+                Statement::CompoundAssign {
+                    name: "MAX".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Number(1),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Cannot assign to constant: MAX".to_string()));
+    }
+
+    #[test]
+    fn test_const_redefining_variable_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "x".to_string(),
+                    value: Expr::Number(1),
+                },
+                Statement::Const {
+                    name: "x".to_string(),
+                    value: Expr::Number(2),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Variable already defined: x".to_string()));
+        assert!(!interpreter.is_constant("x"));
+    }
+
+    // ==================== Declaration Tests ====================
+
+    #[test]
+    fn test_declare_with_value() {
+        let interpreter = run_program(vec![
+            Statement::Declare {
+                name: "x".to_string(),
+                type_annotation: Some(Type::Integer),
+                value: Some(Expr::Number(7)),
+            },
+        ]);
+        assert_eq!(interpreter.get_variable("x"), Some(7));
+    }
+
+    #[test]
+    fn test_declare_without_value_is_uninitialized() {
+        let program = Program {
+            statements: vec![
+                Statement::Declare {
+                    name: "x".to_string(),
+                    type_annotation: None,
+                    value: None,
+                },
+                Statement::Assign {
+                    name: "y".to_string(),
+                    value: Expr::Identifier("x".to_string()),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Uninitialized variable: x".to_string()));
+        assert_eq!(interpreter.get_variable("x"), None);
+    }
+
+    #[test]
+    fn test_declared_boolean_rejects_other_values() {
+        let program = Program {
+            statements: vec![
+                Statement::Declare {
+                    name: "flag".to_string(),
+                    type_annotation: Some(Type::Boolean),
+                    value: None,
+                },
+                Statement::Assign {
+                    name: "flag".to_string(),
+                    value: Expr::BinaryOp {
+                        left: Box::new(Expr::Number(3)),
+                        operator: Operator::Gt,
+                        right: Box::new(Expr::Number(2)),
+                    },
+                },
+                Statement::Assign {
+                    name: "flag".to_string(),
+                    value: Expr::Number(5),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(
+            result,
+            Err("Type mismatch: cannot assign 5 to flag of type booleano".to_string())
+        );
+        assert_eq!(interpreter.get_variable("flag"), Some(1));
+    }
+
+    #[test]
+    fn test_declared_boolean_rejects_compound_result() {
+        let program = Program {
+            statements: vec![
+                Statement::Declare {
+                    name: "flag".to_string(),
+                    type_annotation: Some(Type::Boolean),
+                    value: Some(Expr::Number(1)),
+                },
+                Statement::CompoundAssign {
+                    name: "flag".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Number(1),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(
+            result,
+            Err("Type mismatch: cannot assign 2 to flag of type booleano".to_string())
+        );
+    }
+
+    #[test]
+    fn test_redeclare_variable_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "x".to_string(),
+                    value: Expr::Number(1),
+                },
+                Statement::Declare {
+                    name: "x".to_string(),
+                    type_annotation: None,
+                    value: None,
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Variable already defined: x".to_string()));
+    }
+
+    #[test]
+    fn test_strict_mode_requires_declaration() {
+        let program = Program {
+            statements: vec![
+                Statement::Declare {
+                    name: "x".to_string(),
+                    type_annotation: Some(Type::Integer),
+                    value: None,
+                },
+                Statement::Assign {
+                    name: "x".to_string(),
+                    value: Expr::Number(1),
+                },
+                Statement::Assign {
+                    name: "y".to_string(),
+                    value: Expr::Number(2),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        interpreter.set_strict_mode(true);
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Undeclared variable: y".to_string()));
+        assert_eq!(interpreter.get_variable("x"), Some(1));
+        assert_eq!(interpreter.get_variable("y"), None);
+    }
+
+    #[test]
+    fn test_strict_mode_accepts_constants() {
+        let program = Program {
+            statements: vec![
+                Statement::Const {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(10),
+                },
+                Statement::Declare {
+                    name: "x".to_string(),
+                    type_annotation: None,
+                    value: Some(Expr::Identifier("MAX".to_string())),
+                },
+                Statement::CompoundAssign {
+                    name: "x".to_string(),
+                    operator: Operator::Minus,
+                    value: Expr::Number(3),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        interpreter.set_strict_mode(true);
+        assert!(interpreter.run(&program).is_ok());
+        assert_eq!(interpreter.get_variable("x"), Some(7));
+    }
+
+    // ==================== Overflow Tests ====================
+
+    #[test]
+    fn test_integer_overflow_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "big".to_string(),
+                    value: Expr::BinaryOp {
+                        left: Box::new(Expr::Number(i64::MAX)),
+                        operator: Operator::Plus,
+                        right: Box::new(Expr::Number(1)),
+                    },
+                }
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Integer overflow".to_string()));
+    }
+
+    #[test]
+    fn test_division_overflow_error() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "min".to_string(),
+                    value: Expr::BinaryOp {
+                        left: Box::new(Expr::Number(0)),
+                        operator: Operator::Minus,
+                        right: Box::new(Expr::Number(i64::MAX)),
+                    },
+                },
+                Statement::CompoundAssign {
+                    name: "min".to_string(),
+                    operator: Operator::Minus,
+                    value: Expr::Number(1),
+                },
+                Statement::CompoundAssign {
+                    name: "min".to_string(),
+                    operator: Operator::Divide,
+                    value: Expr::BinaryOp {
+                        left: Box::new(Expr::Number(0)),
+                        operator: Operator::Minus,
+                        right: Box::new(Expr::Number(1)),
+                    },
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        let result = interpreter.run(&program);
+        assert_eq!(result, Err("Integer overflow".to_string()));
+        assert_eq!(interpreter.get_variable("min"), Some(i64::MIN));
+    }
+
+    // ==================== Borrowed Program Tests ====================
+
+    #[test]
+    fn test_program_can_run_twice() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "x".to_string(),
+                    value: Expr::Number(1),
+                },
+                Statement::CompoundAssign {
+                    name: "x".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Number(1),
+                },
+            ],
+            lines: Vec::new(),
+        };
+
+        let mut first = Interpreter::new();
+        first.run(&program).unwrap();
+        let mut second = Interpreter::new();
+        second.run(&program).unwrap();
+
+        assert_eq!(first.get_variable("x"), Some(2));
+        assert_eq!(second.get_variable("x"), Some(2));
+        assert_eq!(program.statements.len(), 2);
+    }
+
+    #[test]
+    fn test_same_interpreter_reruns_program_body() {
+        let program = Program {
+            statements: vec![
+                Statement::CompoundAssign {
+                    name: "total".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Identifier("step".to_string()),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = run_program(vec![
+            Statement::Assign {
+                name: "total".to_string(),
+                value: Expr::Number(0),
+            },
+            Statement::Assign {
+                name: "step".to_string(),
+                value: Expr::Number(5),
+            },
+        ]);
+
+        for _ in 0..3 {
+            interpreter.run(&program).unwrap();
+        }
+        assert_eq!(interpreter.get_variable("total"), Some(15));
+    }
+
+    #[test]
+    fn test_prepared_program_runs_repeatedly() {
+        let program = Parser::new(tokenize("total += step\nf(total)".to_string()).unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("total", 0).unwrap();
+        interpreter.set_variable("step", 5).unwrap();
+        let resolved = interpreter.prepare(&program);
+        interpreter.register_function("f", |args| Ok(args[0]));
+        for _ in 0..3 {
+            interpreter.run_prepared(&resolved).unwrap();
+        }
+        assert_eq!(interpreter.get_variable("total"), Some(15));
+        assert_eq!(resolved.program(), &program);
+    }
+
+    #[test]
+    fn test_prepared_program_on_other_interpreter() {
+        let program = Parser::new(tokenize("x = 2\ny = x * 3".to_string()).unwrap()).parse().unwrap();
+        let resolved = Interpreter::new().prepare(&program);
+
+        let mut fresh = Interpreter::new();
+        fresh.run_prepared(&resolved).unwrap();
+        assert_eq!(fresh.get_variable("y"), Some(6));
+
+        let mut other = Interpreter::new();
+        other.set_variable("z", 1).unwrap();
+        assert_eq!(
+            other.run_prepared(&resolved),
+            Err("Program was prepared by an interpreter with other variables".to_string())
+        );
+    }
+
+    #[test]
+    fn test_prepared_program_with_other_functions() {
+        let program = Parser::new(tokenize("y = g(1)".to_string()).unwrap()).parse().unwrap();
+        let mut preparer = Interpreter::new();
+        preparer.register_function("f", |_| Ok(100));
+        preparer.register_function("g", |args| Ok(args[0]));
+        let resolved = preparer.prepare(&program);
+
+        let mut same = Interpreter::new();
+        same.register_function("f", |_| Ok(100));
+        same.register_function("g", |args| Ok(args[0] + 1));
+        same.run_prepared(&resolved).unwrap();
+        assert_eq!(same.get_variable("y"), Some(2));
+
+        let mut swapped = Interpreter::new();
+        swapped.register_function("g", |args| Ok(args[0]));
+        swapped.register_function("f", |_| Ok(100));
+        let mut fewer = Interpreter::new();
+        fewer.register_function("h", |_| Ok(0));
+        for mut other in [swapped, fewer] {
+            assert_eq!(
+                other.run_prepared(&resolved),
+                Err("Program was prepared by an interpreter with other functions".to_string())
+            );
+            assert_eq!(other.get_variable("y"), None);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_expr_by_reference() {
+        let interpreter = run_program(vec![
+            Statement::Assign {
+                name: "x".to_string(),
+                value: Expr::Number(6),
+            },
+        ]);
+        let expr = Expr::BinaryOp {
+            left: Box::new(Expr::Identifier("x".to_string())),
+            operator: Operator::Multiply,
+            right: Box::new(Expr::Number(7)),
+        };
+
+        assert_eq!(interpreter.evaluate_expr(&expr), Ok(42));
+        assert_eq!(interpreter.evaluate_expr(&expr), Ok(42));
+        assert_eq!(interpreter.get_variable("x"), Some(6));
+    }
+
+    // ==================== Slot Resolution Tests ====================
+
+    #[test]
+    fn test_unassigned_name_has_no_value() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "a".to_string(),
+                    value: Expr::Number(1),
+                },
+                Statement::Assign {
+                    name: "b".to_string(),
+                    value: Expr::Identifier("ghost".to_string()),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&program), Err("Undefined variable: ghost".to_string()));
+        assert_eq!(interpreter.get_variable("a"), Some(1));
+        assert_eq!(interpreter.get_variable("b"), None);
+        assert_eq!(interpreter.get_variable("ghost"), None);
+        assert_eq!(
+            interpreter.evaluate_expr(&Expr::Identifier("ghost".to_string())),
+            Err("Undefined variable: ghost".to_string())
+        );
+    }
+
+    #[test]
+    fn test_slots_shared_across_programs() {
+        let mut interpreter = run_program(vec![
+            Statement::Assign {
+                name: "x".to_string(),
+                value: Expr::Number(2),
+            },
+        ]);
+        let second = Program {
+            statements: vec![
+                Statement::Assign {
+                    name: "y".to_string(),
+                    value: Expr::BinaryOp {
+                        left: Box::new(Expr::Identifier("x".to_string())),
+                        operator: Operator::Multiply,
+                        right: Box::new(Expr::Number(10)),
+                    },
+                },
+                Statement::CompoundAssign {
+                    name: "x".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Identifier("y".to_string()),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        interpreter.run(&second).unwrap();
+        assert_eq!(interpreter.get_variable("x"), Some(22));
+        assert_eq!(interpreter.get_variable("y"), Some(20));
+    }
+
+    // ==================== Execution Hook Tests ====================
+
+    struct StopAt {
+        index: usize,
+        seen: Vec<(usize, Option<i64>)>,
+        finished: Vec<usize>,
+    }
+
+    impl ExecutionHook for StopAt {
+        fn before_statement(&mut self, interpreter: &Interpreter, _program: &Program, index: usize) -> ControlFlow<()> {
+            self.seen.push((index, interpreter.get_variable("x")));
+            if index == self.index { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        }
+
+        fn after_statement(&mut self, _interpreter: &Interpreter, _program: &Program, index: usize) {
+            self.finished.push(index);
+        }
+    }
+
+    #[test]
+    fn test_run_with_hook_observes_and_stops() {
+        let program = Program {
+            statements: vec![
+                Statement::Assign { name: "x".to_string(), value: Expr::Number(1) },
+                Statement::CompoundAssign {
+                    name: "x".to_string(),
+                    operator: Operator::Plus,
+                    value: Expr::Number(1),
+                },
+                Statement::Assign { name: "y".to_string(), value: Expr::Number(3) },
+            ],
+            lines: Vec::new(),
+        };
+        let mut hook = StopAt { index: 2, seen: Vec::new(), finished: Vec::new() };
+        let mut interpreter = Interpreter::new();
+        interpreter.run_with_hook(&program, &mut hook).unwrap();
+        assert_eq!(hook.seen, vec![(0, None), (1, Some(1)), (2, Some(2))]);
+        assert_eq!(hook.finished, vec![0, 1]);
+        assert_eq!(interpreter.get_variable("y"), None);
+    }
+
+    #[test]
+    fn test_variables_in_first_assignment_order() {
+        let interpreter = run_program(vec![
+            Statement::Assign { name: "b".to_string(), value: Expr::Number(2) },
+            Statement::Declare { name: "c".to_string(), type_annotation: None, value: None },
+            Statement::Assign { name: "a".to_string(), value: Expr::Number(1) },
+        ]);
+        let variables: Vec<_> = interpreter.variables().collect();
+        assert_eq!(variables, vec![("b", 2), ("a", 1)]);
+    }
+
+    // ==================== Limit Tests ====================
+
+    fn run_limited(source: &str, limits: Limits) -> (Interpreter, Result<(), String>) {
+        let program = Parser::new(tokenize(source.to_string()).unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(limits);
+        let result = interpreter.run(&program);
+        (interpreter, result)
+    }
+
+    #[test]
+    fn test_no_limits_by_default() {
+        assert_eq!(Interpreter::new().limits(), Limits::default());
+        let (_, result) = run_limited("a = 1\nb = a + 1", Limits::default());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let limits = Limits { max_steps: Some(2), ..Limits::default() };
+        let (interpreter, result) = run_limited("a = 1\nb = 2\nc = 3", limits);
+        assert_eq!(result, Err("Step limit exceeded: more than 2 statements".to_string()));
+        assert_eq!(interpreter.get_variable("b"), Some(2));
+        assert_eq!(interpreter.get_variable("c"), None);
+
+        let (_, result) = run_limited("a = 1\nb = 2", limits);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_step_limit_applies_per_run() {
+        let program = Parser::new(tokenize("a = 1\nb = 2".to_string()).unwrap()).parse().unwrap();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(Limits { max_steps: Some(2), ..Limits::default() });
+        assert!(interpreter.run(&program).is_ok());
+        let second = Parser::new(tokenize("c = a + b\nd = c".to_string()).unwrap()).parse().unwrap();
+        assert!(interpreter.run(&second).is_ok());
+        assert_eq!(interpreter.get_variable("d"), Some(3));
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let limits = Limits { max_nesting: Some(3), ..Limits::default() };
+        let (_, result) = run_limited("x = 1 + (2 * 3)", limits);
+        assert!(result.is_ok());
+        let (_, result) = run_limited("x = 1 + (2 * (3 - 1))", limits);
+        assert_eq!(result, Err("Nesting limit exceeded: expression nested more than 3 levels".to_string()));
+        let (_, result) = run_limited("x = 1\n((x + 1) * 2) > 3", limits);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_nesting_limit_is_checked_before_running() {
+        let limits = Limits { max_nesting: Some(2), ..Limits::default() };
+        let (interpreter, result) = run_limited("a = 1\nb = 1 + (2 * 3)", limits);
+        assert_eq!(result, Err("Nesting limit exceeded: expression nested more than 2 levels".to_string()));
+        assert_eq!(interpreter.get_variable("a"), None);
+    }
+
+    #[test]
+    fn test_nesting_limit_on_source_before_optimizing() {
+        let program = Parser::new(tokenize("x = 1 + (2 * 3)".to_string()).unwrap()).parse().unwrap();
+        let limits = Limits { max_nesting: Some(2), ..Limits::default() };
+        assert!(limits.check_nesting(&program).is_err());
+        assert!(limits.check_nesting(&pseudocu::optimize::optimize(&program)).is_ok());
+        assert!(Limits::default().check_nesting(&program).is_ok());
+    }
+
+    #[test]
+    fn test_nesting_limit_applies_to_evaluate_expr() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_limits(Limits { max_nesting: Some(1), ..Limits::default() });
+        assert_eq!(interpreter.evaluate_expr(&Expr::Number(4)), Ok(4));
+        let expr = Expr::BinaryOp {
+            left: Box::new(Expr::Number(1)),
+            operator: Operator::Plus,
+            right: Box::new(Expr::Number(2)),
+        };
+        assert!(interpreter.evaluate_expr(&expr).unwrap_err().starts_with("Nesting limit exceeded"));
+    }
+
+    #[test]
+    fn test_variable_limit() {
+        let limits = Limits { max_variables: Some(2), ..Limits::default() };
+        let (_, result) = run_limited("a = 1\nb = 2\na = 3\nb += a", limits);
+        assert!(result.is_ok());
+        for source in ["a = 1\nb = 2\nc = 3", "a = 1\nb = 2\nvar c", "a = 1\nb = 2\nconst C = 3"] {
+            let (interpreter, result) = run_limited(source, limits);
+            assert_eq!(result, Err("Variable limit exceeded: more than 2 variables".to_string()));
+            assert_eq!(interpreter.variables().count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let limits = Limits { timeout: Some(Duration::ZERO), ..Limits::default() };
+        let (_, result) = run_limited("a = 1", limits);
+        assert_eq!(result, Err("Time limit exceeded: ran longer than 0ns".to_string()));
+        let limits = Limits { timeout: Some(Duration::from_secs(60)), ..Limits::default() };
+        let (_, result) = run_limited("a = 1\nb = a * 2", limits);
+        assert!(result.is_ok());
+    }
+
+    // ==================== Embedding Tests ====================
+
+    fn parse(source: &str) -> Program {
+        Parser::new(tokenize(source.to_string()).unwrap()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_register_function() {
+        let mut interpreter = Interpreter::new();
+        interpreter.register_function("max", |args| {
+            args.iter().copied().max().ok_or_else(|| "max needs an argument".to_string())
+        });
+        interpreter.run(&parse("x = max(3, 9, 4) * 2\ny = max(x)")).unwrap();
+        assert_eq!(interpreter.get_variable("x"), Some(18));
+        assert_eq!(interpreter.get_variable("y"), Some(18));
+        assert_eq!(interpreter.run(&parse("z = max()")), Err("max needs an argument".to_string()));
+    }
+
+    #[test]
+    fn test_register_function_replaces_existing() {
+        let mut interpreter = Interpreter::new();
+        interpreter.register_function("f", |_| Ok(1));
+        interpreter.register_function("f", |_| Ok(2));
+        interpreter.run(&parse("x = f()")).unwrap();
+        assert_eq!(interpreter.get_variable("x"), Some(2));
+    }
+
+    #[test]
+    fn test_undefined_function_error() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&parse("x = f(1)")), Err("Undefined function: f".to_string()));
+        assert_eq!(interpreter.run(&parse("f(1 / 0)")), Err("Division by zero".to_string()));
+    }
+
+    #[test]
+    fn test_host_function_captures_output() {
+        let printed = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&printed);
+        let mut interpreter = Interpreter::new();
+        interpreter.register_function("show", move |args| {
+            sink.lock().unwrap().extend_from_slice(args);
+            Ok(0)
+        });
+        interpreter.run(&parse("x = 4\nshow(x, x * x)\nshow()")).unwrap();
+        assert_eq!(*printed.lock().unwrap(), vec![4, 16]);
+    }
+
+    #[test]
+    fn test_engines_are_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Interpreter>();
+        assert_send::<Vm>();
+
+        let mut interpreter = Interpreter::new();
+        interpreter.register_function("double", |args| Ok(args[0] * 2));
+        let worker = std::thread::spawn(move || {
+            interpreter.run(&parse("x = double(21)")).unwrap();
+            interpreter
+        });
+        assert_eq!(worker.join().unwrap().get_variable("x"), Some(42));
+    }
+
+    #[test]
+    fn test_set_variable() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_strict_mode(true);
+        interpreter.set_variable("n", 5).unwrap();
+        interpreter.run(&parse("var total = n * 2\nn += 1")).unwrap();
+        assert_eq!(interpreter.get_variable("total"), Some(10));
+        assert_eq!(interpreter.get_variable("n"), Some(6));
+
+        interpreter.run(&parse("const K = 1\nvar b: booleano")).unwrap();
+        assert_eq!(interpreter.set_variable("K", 2), Err("Cannot assign to constant: K".to_string()));
+        assert_eq!(
+            interpreter.set_variable("b", 2),
+            Err("Type mismatch: cannot assign 2 to b of type booleano".to_string())
+        );
+    }
+
+    #[test]
+    fn test_declaring_a_set_variable() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("n", 5).unwrap();
+        interpreter.set_variable("m", 1).unwrap();
+        interpreter.run(&parse("var n: entero\nvar m = 7\ntotal = n + m")).unwrap();
+        assert_eq!(interpreter.get_variable("total"), Some(12));
+
+        interpreter.set_variable("b", 2).unwrap();
+        assert_eq!(
+            interpreter.run(&parse("var b: booleano")),
+            Err("Type mismatch: cannot assign 2 to b of type booleano".to_string())
+        );
+        assert_eq!(interpreter.run(&parse("var n")), Err("Variable already defined: n".to_string()));
+
+        let mut interpreter = Interpreter::new();
+        interpreter.set_variable("x", 1).unwrap();
+        assert_eq!(interpreter.run(&parse("x = 2\nvar x")), Err("Variable already defined: x".to_string()));
+    }
+
+    #[test]
+    fn test_write_variables() {
+        let mut interpreter = Interpreter::new();
+        interpreter.run(&parse("b = 2\na = 1\nvar c")).unwrap();
+        let mut out = Vec::new();
+        interpreter.write_variables(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a = 1\nb = 2\n");
+        assert_eq!(interpreter.variables().collect::<Vec<_>>(), vec![("b", 2), ("a", 1)]);
+    }
+
+    // ==================== Output Tests ====================
+
+    #[test]
+    fn test_print_variables_to_output() {
+        let output = SharedBuffer::new();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(output.clone());
+        interpreter.run(&parse("d = 5\na = 4\nresult = d + a\nvar unset")).unwrap();
+        interpreter.print_variables().unwrap();
+        assert_eq!(output.contents(), "a = 4\nd = 5\nresult = 9\n");
+    }
+
+    #[test]
+    fn test_print_variables_appends_to_output() {
+        let output = SharedBuffer::new();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(output.clone());
+        interpreter.run(&parse("x = 1")).unwrap();
+        interpreter.print_variables().unwrap();
+        interpreter.run(&parse("x = 0 - 2")).unwrap();
+        interpreter.print_variables().unwrap();
+        assert_eq!(output.take(), "x = 1\nx = -2\n");
+        assert_eq!(output.contents(), "");
+    }
+
+    #[test]
+    fn test_variables_table() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.variables_table(), "");
+        interpreter.run(&parse("const K = 3\nb = K > 2\na = K * 2")).unwrap();
+        assert_eq!(interpreter.variables_table(), "K = 3\na = 6\nb = 1\n");
+    }
+
+    #[test]
+    fn test_host_function_writes_to_output() {
+        let output = SharedBuffer::new();
+        let log = output.clone();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_output(output.clone());
+        interpreter.register_function("show", move |args| {
+            writeln!(log.clone(), "show {:?}", args).map_err(|e| e.to_string())?;
+            Ok(0)
+        });
+        interpreter.run(&parse("x = 2\nshow(x, x + 1)")).unwrap();
+        interpreter.print_variables().unwrap();
+        assert_eq!(output.contents(), "show [2, 3]\nx = 2\n");
+    }
 }
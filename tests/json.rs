@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::json::Json;
+
+    #[test]
+    fn test_parse_scalars() {
+        assert_eq!(Json::parse("null"), Ok(Json::Null));
+        assert_eq!(Json::parse(" true "), Ok(Json::Bool(true)));
+        assert_eq!(Json::parse("-42"), Ok(Json::Int(-42)));
+        assert_eq!(Json::parse("1.5e2"), Ok(Json::Float(150.0)));
+        assert_eq!(Json::parse("\"a\\n\\u00f1\\ud83d\\ude00\""), Ok(Json::string("a\nñ😀")));
+    }
+
+    #[test]
+    fn test_parse_nested() {
+        let value = Json::parse(r#"{"a": [1, {"b": "c"}], "d": {}}"#).unwrap();
+        assert_eq!(value.pointer(&["a"]).and_then(Json::as_array).map(|values| values.len()), Some(2));
+        assert_eq!(value.get("a").unwrap().as_array().unwrap()[1].get("b").and_then(Json::as_str), Some("c"));
+        assert_eq!(value.get("d"), Some(&Json::Object(Vec::new())));
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Json::parse("").is_err());
+        assert!(Json::parse("[1,").is_err());
+        assert!(Json::parse("{\"a\" 1}").is_err());
+        assert!(Json::parse("\"open").is_err());
+        assert!(Json::parse("1 2").is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let value = Json::object([
+            ("name", Json::string("x \"quoted\"\n")),
+            ("values", Json::Array(vec![Json::Int(1), Json::Null, Json::Bool(false)])),
+        ]);
+        assert_eq!(value.to_string(), r#"{"name":"x \"quoted\"\n","values":[1,null,false]}"#);
+        assert_eq!(Json::parse(&value.to_string()), Ok(value));
+    }
+}
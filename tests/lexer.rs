@@ -1,4 +1,4 @@
-use pseudocu::lexer::{Lexer, Token, TokenType};
+use pseudocu::lexer::{tokenize_with_columns, tokenize_with_comments, Comment, Lexer, Token, TokenType};
 
 #[test]
 fn test_basic_expression() {
@@ -381,4 +381,166 @@ fn test_complete_assignment_program() {
     assert_eq!(lexer.next_token().token_type, TokenType::Ident("a".to_string()));
     
     assert_eq!(lexer.next_token().token_type, TokenType::EOF);
-}
\ No newline at end of file
+}
+// ==================== Compound Assignment Tests ====================
+
+#[test]
+fn test_compound_assignment_operators() {
+    let input = "a += 1\nb -= 2\nc *= 3\nd /= 4".to_string();
+    let mut lexer = Lexer::new(input);
+
+    let expected = [
+        (TokenType::Ident("a".to_string()), "a", 1),
+        (TokenType::PlusAssign, "+=", 1),
+        (TokenType::Number(1), "1", 1),
+        (TokenType::Ident("b".to_string()), "b", 2),
+        (TokenType::MinusAssign, "-=", 2),
+        (TokenType::Number(2), "2", 2),
+        (TokenType::Ident("c".to_string()), "c", 3),
+        (TokenType::StarAssign, "*=", 3),
+        (TokenType::Number(3), "3", 3),
+        (TokenType::Ident("d".to_string()), "d", 4),
+        (TokenType::SlashAssign, "/=", 4),
+        (TokenType::Number(4), "4", 4),
+        (TokenType::EOF, "", 4),
+    ];
+
+    for (token_type, literal, line) in expected {
+        assert_eq!(lexer.next_token(), Token {
+            token_type,
+            literal: literal.to_string(),
+            line,
+        });
+    }
+}
+
+#[test]
+fn test_operator_followed_by_space_is_not_compound() {
+    let input = "x + = 1".to_string();
+    let mut lexer = Lexer::new(input);
+
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident("x".to_string()));
+    assert_eq!(lexer.next_token().token_type, TokenType::Plus);
+    assert_eq!(lexer.next_token().token_type, TokenType::Assign);
+    assert_eq!(lexer.next_token().token_type, TokenType::Number(1));
+    assert_eq!(lexer.next_token().token_type, TokenType::EOF);
+}
+
+// ==================== Keyword Tests ====================
+
+#[test]
+fn test_const_keyword() {
+    let input = "const PI_APPROX = 314".to_string();
+    let mut lexer = Lexer::new(input);
+
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::Const,
+        literal: "const".to_string(),
+        line: 1,
+    });
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::Ident("PI_APPROX".to_string()),
+        literal: "PI_APPROX".to_string(),
+        line: 1,
+    });
+    assert_eq!(lexer.next_token().token_type, TokenType::Assign);
+    assert_eq!(lexer.next_token().token_type, TokenType::Number(314));
+    assert_eq!(lexer.next_token().token_type, TokenType::EOF);
+}
+
+#[test]
+fn test_identifier_starting_with_keyword() {
+    let input = "constant".to_string();
+    let mut lexer = Lexer::new(input);
+
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident("constant".to_string()));
+}
+
+#[test]
+fn test_var_declaration_with_type() {
+    let input = "var x: entero".to_string();
+    let mut lexer = Lexer::new(input);
+
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::Var,
+        literal: "var".to_string(),
+        line: 1,
+    });
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident("x".to_string()));
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::Colon,
+        literal: ":".to_string(),
+        line: 1,
+    });
+    assert_eq!(lexer.next_token().token_type, TokenType::Ident("entero".to_string()));
+    assert_eq!(lexer.next_token().token_type, TokenType::EOF);
+}
+
+#[test]
+fn test_parentheses() {
+    let input = "(1)".to_string();
+    let mut lexer = Lexer::new(input);
+
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::LParen,
+        literal: "(".to_string(),
+        line: 1,
+    });
+    assert_eq!(lexer.next_token().token_type, TokenType::Number(1));
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::RParen,
+        literal: ")".to_string(),
+        line: 1,
+    });
+    assert_eq!(lexer.next_token().token_type, TokenType::EOF);
+}
+
+#[test]
+fn test_comments_skipped() {
+    let input = "# header\nx = 1 # one\n#last".to_string();
+    let mut lexer = Lexer::new(input);
+
+    assert_eq!(lexer.next_token(), Token {
+        token_type: TokenType::Ident("x".to_string()),
+        literal: "x".to_string(),
+        line: 2,
+    });
+    assert_eq!(lexer.next_token().token_type, TokenType::Assign);
+    assert_eq!(lexer.next_token().token_type, TokenType::Number(1));
+    assert_eq!(lexer.next_token().token_type, TokenType::EOF);
+    assert_eq!(lexer.comments(), &[
+        Comment { text: " header".to_string(), line: 1, trailing: false },
+        Comment { text: " one".to_string(), line: 2, trailing: true },
+        Comment { text: "last".to_string(), line: 3, trailing: false },
+    ]);
+}
+
+#[test]
+fn test_tokenize_with_comments() {
+    let (tokens, comments) = tokenize_with_comments("x = 1  # set x  \n".to_string()).unwrap();
+
+    assert_eq!(tokens.len(), 4);
+    assert_eq!(comments, vec![Comment { text: " set x".to_string(), line: 1, trailing: true }]);
+}
+
+#[test]
+fn test_call_tokens() {
+    let tokens = tokenize_with_comments("f(a, 1)".to_string()).unwrap().0;
+    let types: Vec<_> = tokens.into_iter().map(|token| token.token_type).collect();
+    assert_eq!(types, vec![
+        TokenType::Ident("f".to_string()),
+        TokenType::LParen,
+        TokenType::Ident("a".to_string()),
+        TokenType::Comma,
+        TokenType::Number(1),
+        TokenType::RParen,
+        TokenType::EOF,
+    ]);
+}
+
+#[test]
+fn test_tokenize_with_columns() {
+    let tokens = tokenize_with_columns("x = 1\n  y += 2x # x\n".to_string()).unwrap();
+    let columns: Vec<_> = tokens.iter().map(|(token, column)| (token.line, *column)).collect();
+    assert_eq!(columns, vec![(1, 0), (1, 2), (1, 4), (2, 2), (2, 4), (2, 7), (2, 8), (3, 0)]);
+}
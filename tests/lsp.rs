@@ -0,0 +1,257 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use pseudocu::json::Json;
+    use pseudocu::lsp::{self, Server};
+
+    const URI: &str = "file:///program.pc";
+
+    fn open(server: &mut Server, text: &str) -> Vec<Json> {
+        server.handle(&Json::object([
+            ("jsonrpc", Json::string("2.0")),
+            ("method", Json::string("textDocument/didOpen")),
+            ("params", Json::object([("textDocument", Json::object([
+                ("uri", Json::string(URI)),
+                ("languageId", Json::string("pseudocu")),
+                ("version", Json::Int(1)),
+                ("text", Json::string(text)),
+            ]))])),
+        ]))
+    }
+
+    fn request(server: &mut Server, method: &str, position: Option<(i64, i64)>) -> Json {
+        let mut params = vec![("textDocument".to_string(), Json::object([("uri", Json::string(URI))]))];
+        if let Some((line, character)) = position {
+            params.push(("position".to_string(), Json::object([
+                ("line", Json::Int(line)),
+                ("character", Json::Int(character)),
+            ])));
+        }
+        let mut replies = server.handle(&Json::object([
+            ("jsonrpc", Json::string("2.0")),
+            ("id", Json::Int(1)),
+            ("method", Json::string(method)),
+            ("params", Json::Object(params)),
+        ]));
+        assert_eq!(replies.len(), 1);
+        replies.remove(0).get("result").cloned().unwrap()
+    }
+
+    fn diagnostics(replies: &[Json]) -> Vec<(i64, String)> {
+        replies[0]
+            .pointer(&["params", "diagnostics"])
+            .and_then(Json::as_array)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| (
+                diagnostic.pointer(&["range", "start", "line"]).and_then(Json::as_i64).unwrap(),
+                diagnostic.get("message").and_then(Json::as_str).unwrap().to_string(),
+            ))
+            .collect()
+    }
+
+    fn range(value: &Json) -> (i64, i64, i64) {
+        let get = |path: &[&str]| value.pointer(path).and_then(Json::as_i64).unwrap();
+        (get(&["start", "line"]), get(&["start", "character"]), get(&["end", "character"]))
+    }
+
+    fn frame(message: &Json) -> String {
+        let body = message.to_string();
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    // ==================== Diagnostics Tests ====================
+
+    #[test]
+    fn test_valid_document_has_no_diagnostics() {
+        let mut server = Server::new();
+        let replies = open(&mut server, "x = 1\ny = x + 2");
+        assert_eq!(replies[0].get("method").and_then(Json::as_str), Some("textDocument/publishDiagnostics"));
+        assert!(diagnostics(&replies).is_empty());
+    }
+
+    #[test]
+    fn test_parser_error_diagnostic() {
+        let mut server = Server::new();
+        let replies = open(&mut server, "x = 1\ny = (x + 2");
+        assert_eq!(diagnostics(&replies), vec![(1, "Expected ) after expression, found: EOF".to_string())]);
+    }
+
+    #[test]
+    fn test_unexpected_character_diagnostic() {
+        let mut server = Server::new();
+        let replies = open(&mut server, "x = 1\ny = x $ 2");
+        assert_eq!(diagnostics(&replies), vec![(1, "Unexpected character: $".to_string())]);
+        let range = range(replies[0].pointer(&["params", "diagnostics"]).unwrap().as_array().unwrap()[0].get("range").unwrap());
+        assert_eq!(range, (1, 6, 7));
+    }
+
+    #[test]
+    fn test_change_updates_diagnostics() {
+        let mut server = Server::new();
+        open(&mut server, "x = (");
+        let replies = server.handle(&Json::parse(&format!(
+            r#"{{"jsonrpc":"2.0","method":"textDocument/didChange","params":{{"textDocument":{{"uri":"{}","version":2}},"contentChanges":[{{"text":"x = 1"}}]}}}}"#,
+            URI
+        )).unwrap());
+        assert!(diagnostics(&replies).is_empty());
+    }
+
+    // ==================== Navigation Tests ====================
+
+    #[test]
+    fn test_definition_is_first_assignment() {
+        let mut server = Server::new();
+        open(&mut server, "a = 1\nx = a\nx += 2\ny = x * x");
+        let result = request(&mut server, "textDocument/definition", Some((3, 9)));
+        assert_eq!(result.get("uri").and_then(Json::as_str), Some(URI));
+        assert_eq!(range(result.get("range").unwrap()), (1, 0, 1));
+    }
+
+    #[test]
+    fn test_definition_of_declaration() {
+        let mut server = Server::new();
+        open(&mut server, "# setup\nvar total: entero\ntotal = 3");
+        let result = request(&mut server, "textDocument/definition", Some((2, 2)));
+        assert_eq!(range(result.get("range").unwrap()), (1, 4, 9));
+    }
+
+    #[test]
+    fn test_definition_outside_identifier() {
+        let mut server = Server::new();
+        open(&mut server, "x = 1 + 2");
+        assert_eq!(request(&mut server, "textDocument/definition", Some((0, 7))), Json::Null);
+    }
+
+    #[test]
+    fn test_definition_uses_token_columns() {
+        let mut server = Server::new();
+        open(&mut server, "x = 1\ny = 2x  # x");
+        let result = request(&mut server, "textDocument/definition", Some((1, 5)));
+        assert_eq!(range(result.get("range").unwrap()), (0, 0, 1));
+    }
+
+    #[test]
+    fn test_hover_shows_type_and_value() {
+        let mut server = Server::new();
+        open(&mut server, "const K = 3\nvar f: booleano = 1\nx = K * 2");
+        let hover = |server: &mut Server, line, character| {
+            request(server, "textDocument/hover", Some((line, character)))
+                .pointer(&["contents", "value"])
+                .and_then(Json::as_str)
+                .map(str::to_string)
+        };
+        assert_eq!(hover(&mut server, 2, 4), Some("```pseudocu\nconst K: entero = 3\n```".to_string()));
+        assert_eq!(hover(&mut server, 1, 4), Some("```pseudocu\nvar f: booleano = 1\n```".to_string()));
+        assert_eq!(hover(&mut server, 1, 9), None);
+    }
+
+    #[test]
+    fn test_hover_without_value_after_runtime_error() {
+        let mut server = Server::new();
+        open(&mut server, "x = 5\ny = x / 0");
+        let result = request(&mut server, "textDocument/hover", Some((0, 0)));
+        assert_eq!(result.pointer(&["contents", "value"]).and_then(Json::as_str), Some("```pseudocu\nvar x: entero\n```"));
+    }
+
+    #[test]
+    fn test_hover_without_value_past_step_limit() {
+        let mut server = Server::new();
+        open(&mut server, &"x = 2\n".repeat(100_001));
+        let result = request(&mut server, "textDocument/hover", Some((0, 0)));
+        assert_eq!(result.pointer(&["contents", "value"]).and_then(Json::as_str), Some("```pseudocu\nvar x: entero\n```"));
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let mut server = Server::new();
+        open(&mut server, "const K = 3\nx = K\nx = 2  # again\nvar y");
+        let symbols = request(&mut server, "textDocument/documentSymbol", None);
+        let symbols: Vec<_> = symbols.as_array().unwrap().iter().map(|symbol| (
+            symbol.get("name").and_then(Json::as_str).unwrap().to_string(),
+            symbol.get("kind").and_then(Json::as_i64).unwrap(),
+            range(symbol.get("selectionRange").unwrap()),
+        )).collect();
+        assert_eq!(symbols, vec![
+            ("K".to_string(), 14, (0, 6, 7)),
+            ("x".to_string(), 13, (1, 0, 1)),
+            ("y".to_string(), 13, (3, 4, 5)),
+        ]);
+    }
+
+    #[test]
+    fn test_completion_lists_identifiers_and_keywords() {
+        let mut server = Server::new();
+        open(&mut server, "const K = 3\nvar f: booleano\nx = K + undefined");
+        let items = request(&mut server, "textDocument/completion", Some((2, 0)));
+        let labels: Vec<_> = items.as_array().unwrap().iter()
+            .map(|item| item.get("label").and_then(Json::as_str).unwrap().to_string())
+            .collect();
+        assert_eq!(labels, vec!["K", "f", "x", "undefined", "const", "var", "entero", "booleano"]);
+    }
+
+    // ==================== Protocol Tests ====================
+
+    #[test]
+    fn test_unknown_method() {
+        let mut server = Server::new();
+        let replies = server.handle(&Json::parse(r#"{"jsonrpc":"2.0","id":9,"method":"workspace/unknown"}"#).unwrap());
+        assert_eq!(replies[0].pointer(&["error", "code"]).and_then(Json::as_i64), Some(-32601));
+        assert_eq!(replies[0].get("id"), Some(&Json::Int(9)));
+    }
+
+    #[test]
+    fn test_run_over_streams() {
+        let mut input = String::new();
+        input.push_str(&frame(&Json::parse(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#).unwrap()));
+        input.push_str(&frame(&Json::parse(r#"{"jsonrpc":"2.0","id":2,"method":"shutdown"}"#).unwrap()));
+        input.push_str(&frame(&Json::parse(r#"{"jsonrpc":"2.0","method":"exit"}"#).unwrap()));
+
+        let mut output = Vec::new();
+        let code = lsp::run(Cursor::new(input), &mut output).unwrap();
+        assert_eq!(code, 0);
+
+        let mut reader = Cursor::new(output);
+        let initialize = lsp::read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(initialize.pointer(&["result", "capabilities", "hoverProvider"]), Some(&Json::Bool(true)));
+        let shutdown = lsp::read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(shutdown.get("result"), Some(&Json::Null));
+        assert_eq!(lsp::read_message(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn test_exit_without_shutdown() {
+        let input = frame(&Json::parse(r#"{"jsonrpc":"2.0","method":"exit"}"#).unwrap());
+        let code = lsp::run(Cursor::new(input), Vec::new()).unwrap();
+        assert_eq!(code, 1);
+    }
+
+    #[test]
+    fn test_parse_error_reply_and_keep_reading() {
+        let mut input = String::from("Content-Length: 7\r\n\r\n{\"id\":}");
+        input.push_str("Content-Type: application/json\r\n\r\n");
+        input.push_str(&frame(&Json::parse(r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#).unwrap()));
+        input.push_str(&frame(&Json::parse(r#"{"jsonrpc":"2.0","method":"exit"}"#).unwrap()));
+
+        let mut output = Vec::new();
+        let code = lsp::run(Cursor::new(input), &mut output).unwrap();
+        assert_eq!(code, 0);
+
+        let mut reader = Cursor::new(output);
+        for _ in 0..2 {
+            let reply = lsp::read_message(&mut reader).unwrap().unwrap();
+            assert_eq!(reply.pointer(&["error", "code"]).and_then(Json::as_i64), Some(-32700));
+            assert_eq!(reply.get("id"), Some(&Json::Null));
+        }
+        let shutdown = lsp::read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(shutdown.get("result"), Some(&Json::Null));
+    }
+
+    #[test]
+    fn test_content_length_over_limit() {
+        let mut reader = Cursor::new(format!("Content-Length: {}\r\n\r\n", lsp::MAX_CONTENT_LENGTH + 1));
+        let error = lsp::read_message(&mut reader).unwrap_err();
+        assert_eq!(error.to_string(), "Content-Length 16777217 exceeds the limit of 16777216 bytes");
+        assert_eq!(lsp::read_message(&mut reader).unwrap(), None);
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Operator, Program, Statement};
+    use pseudocu::interpreter::Interpreter;
+    use pseudocu::lexer::tokenize;
+    use pseudocu::optimize::{optimize, optimize_expr};
+    use pseudocu::parser::Parser;
+
+    fn parse_program(input: &str) -> Program {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse().unwrap()
+    }
+
+    fn parse_expr(input: &str) -> Expr {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse_expression().unwrap()
+    }
+
+    fn identifier(name: &str) -> Expr {
+        Expr::Identifier(name.to_string())
+    }
+
+    // ==================== Constant Folding Tests ====================
+
+    #[test]
+    fn test_fold_constant_expression() {
+        assert_eq!(optimize_expr(&parse_expr("60 * 60 * 24")), Expr::Number(86400));
+    }
+
+    #[test]
+    fn test_fold_comparison() {
+        assert_eq!(optimize_expr(&parse_expr("2 + 2 == 4")), Expr::Number(1));
+    }
+
+    #[test]
+    fn test_fold_constant_subtree() {
+        assert_eq!(
+            optimize_expr(&parse_expr("x + 2 * 3")),
+            Expr::BinaryOp {
+                left: Box::new(identifier("x")),
+                operator: Operator::Plus,
+                right: Box::new(Expr::Number(6)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_division_by_zero_is_not_folded() {
+        let expr = parse_expr("10 / 0");
+        assert_eq!(optimize_expr(&expr), expr);
+    }
+
+    #[test]
+    fn test_overflow_is_not_folded() {
+        let expr = parse_expr("9223372036854775807 * 2");
+        assert_eq!(optimize_expr(&expr), expr);
+    }
+
+    // ==================== Identity Tests ====================
+
+    #[test]
+    fn test_simplify_identities() {
+        assert_eq!(optimize_expr(&parse_expr("x * 1")), identifier("x"));
+        assert_eq!(optimize_expr(&parse_expr("1 * x")), identifier("x"));
+        assert_eq!(optimize_expr(&parse_expr("x + 0")), identifier("x"));
+        assert_eq!(optimize_expr(&parse_expr("0 + x")), identifier("x"));
+        assert_eq!(optimize_expr(&parse_expr("x - 0")), identifier("x"));
+        assert_eq!(optimize_expr(&parse_expr("x / 1")), identifier("x"));
+        assert_eq!(optimize_expr(&parse_expr("x * 3 / 3 * 1")), parse_expr("x * 3 / 3"));
+    }
+
+    #[test]
+    fn test_identity_after_folding() {
+        assert_eq!(optimize_expr(&parse_expr("x * 2 - 1 - 1 + 0")), parse_expr("x * 2 - 1 - 1"));
+        assert_eq!(optimize_expr(&parse_expr("x + 1 * 0")), identifier("x"));
+    }
+
+    #[test]
+    fn test_zero_minus_is_kept() {
+        let expr = parse_expr("0 - x");
+        assert_eq!(optimize_expr(&expr), expr);
+    }
+
+    // ==================== Program Tests ====================
+
+    #[test]
+    fn test_remove_dead_expression_statements() {
+        let program = optimize(&parse_program("x = 1\n3 + 4\ny = x\n5"));
+        assert_eq!(
+            program.statements,
+            vec![
+                Statement::Assign {
+                    name: "x".to_string(),
+                    value: Expr::Number(1),
+                },
+                Statement::Assign {
+                    name: "y".to_string(),
+                    value: identifier("x"),
+                },
+            ]
+        );
+        assert_eq!(program.lines, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_expression_statements_that_can_fail_are_kept() {
+        let program = optimize(&parse_program("x\n1 / 0"));
+        assert_eq!(program.statements.len(), 2);
+    }
+
+    #[test]
+    fn test_optimized_program_behaves_the_same() {
+        let source = "const DAY = 60 * 60 * 24\nvar week: entero = DAY * 7\nweek += 0\nratio = week / DAY * 1\nflag = 1 + 1 == 2";
+        let program = parse_program(source);
+        let optimized = optimize(&program);
+
+        let mut original = Interpreter::new();
+        original.run(&program).unwrap();
+        let mut folded = Interpreter::new();
+        folded.run(&optimized).unwrap();
+
+        for name in ["DAY", "week", "ratio", "flag"] {
+            assert_eq!(folded.get_variable(name), original.get_variable(name));
+        }
+    }
+
+    #[test]
+    fn test_runtime_error_preserved_at_original_statement() {
+        let program = optimize(&parse_program("a = 1\nb = a + 2 / 0\nc = 3"));
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.run(&program), Err("Division by zero".to_string()));
+        assert_eq!(interpreter.get_variable("a"), Some(1));
+        assert_eq!(program.line(1), Some(2));
+    }
+
+    // ==================== Call Tests ====================
+
+    #[test]
+    fn test_call_arguments_are_folded() {
+        assert_eq!(
+            optimize_expr(&parse_expr("f(2 * 3, x)")),
+            Expr::Call { name: "f".to_string(), args: vec![Expr::Number(6), identifier("x")] }
+        );
+    }
+
+    #[test]
+    fn test_call_statements_are_kept() {
+        let program = optimize(&parse_program("f()\nx = 1"));
+        assert_eq!(program.statements[0], Statement::Expr(Expr::Call { name: "f".to_string(), args: Vec::new() }));
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use pseudocu::ast::{Expr, Operator, Program, Statement};
+    use pseudocu::ast::{Expr, Operator, Program, Statement, Type};
     use pseudocu::lexer::tokenize;
     use pseudocu::parser::Parser;
 
@@ -429,4 +429,285 @@ mod tests {
             })
         );
     }
-}
\ No newline at end of file
+
+    // ==================== Compound Assignment Tests ====================
+
+    #[test]
+    fn test_compound_assignment_plus() {
+        let result = parse_program("total += x");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        assert_eq!(program.statements.len(), 1);
+        assert_eq!(
+            program.statements[0],
+            Statement::CompoundAssign {
+                name: "total".to_string(),
+                operator: Operator::Plus,
+                value: Expr::Identifier("x".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_compound_assignment_operators() {
+        let result = parse_program("a -= 1\nb *= 2\nc /= 3");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        let operators: Vec<Operator> = program
+            .statements
+            .into_iter()
+            .map(|statement| match statement {
+                Statement::CompoundAssign { operator, .. } => operator,
+                other => panic!("Expected compound assignment, found: {:?}", other),
+            })
+            .collect();
+        assert_eq!(operators, vec![Operator::Minus, Operator::Multiply, Operator::Divide]);
+    }
+
+    #[test]
+    fn test_compound_assignment_expression() {
+        let result = parse_program("x += 2 * y");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        assert_eq!(
+            program.statements[0],
+            Statement::CompoundAssign {
+                name: "x".to_string(),
+                operator: Operator::Plus,
+                value: Expr::BinaryOp {
+                    left: Box::new(Expr::Number(2)),
+                    operator: Operator::Multiply,
+                    right: Box::new(Expr::Identifier("y".to_string())),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_compound_assignment_missing_value() {
+        let result = parse_program("x +=");
+        assert!(result.is_err());
+    }
+
+    // ==================== Constant Tests ====================
+
+    #[test]
+    fn test_const_declaration() {
+        let result = parse_program("const PI_APPROX = 314");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        assert_eq!(
+            program.statements[0],
+            Statement::Const {
+                name: "PI_APPROX".to_string(),
+                value: Expr::Number(314),
+            }
+        );
+    }
+
+    #[test]
+    fn test_assign_to_constant_error() {
+        let result = parse_program("const MAX = 10\nMAX = 20");
+        assert_eq!(result, Err("Cannot assign to constant: MAX at line 2".to_string()));
+    }
+
+    #[test]
+    fn test_compound_assign_to_constant_error() {
+        let result = parse_program("const MAX = 10\nx = 1\nMAX += 1");
+        assert_eq!(result, Err("Cannot assign to constant: MAX at line 3".to_string()));
+    }
+
+    #[test]
+    fn test_redeclare_constant_error() {
+        let result = parse_program("const MAX = 10\nconst MAX = 20");
+        assert_eq!(result, Err("Cannot assign to constant: MAX at line 2".to_string()));
+    }
+
+    #[test]
+    fn test_const_missing_name() {
+        let result = parse_program("const = 5");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_const_missing_value() {
+        let result = parse_program("const MAX 5");
+        assert!(result.is_err());
+    }
+
+    // ==================== Declaration Tests ====================
+
+    #[test]
+    fn test_var_declaration() {
+        let result = parse_program("var x");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        assert_eq!(
+            program.statements[0],
+            Statement::Declare {
+                name: "x".to_string(),
+                type_annotation: None,
+                value: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_var_declaration_with_type() {
+        let result = parse_program("var x: entero\nvar flag: booleano");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        assert_eq!(
+            program.statements,
+            vec![
+                Statement::Declare {
+                    name: "x".to_string(),
+                    type_annotation: Some(Type::Integer),
+                    value: None,
+                },
+                Statement::Declare {
+                    name: "flag".to_string(),
+                    type_annotation: Some(Type::Boolean),
+                    value: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_var_declaration_with_type_and_value() {
+        let result = parse_program("var x: entero = 2 * 3");
+        assert!(result.is_ok());
+        let program = result.unwrap();
+        assert_eq!(
+            program.statements[0],
+            Statement::Declare {
+                name: "x".to_string(),
+                type_annotation: Some(Type::Integer),
+                value: Some(Expr::BinaryOp {
+                    left: Box::new(Expr::Number(2)),
+                    operator: Operator::Multiply,
+                    right: Box::new(Expr::Number(3)),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_var_unknown_type_error() {
+        let result = parse_program("var x: texto");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_var_redeclaring_constant_error() {
+        let result = parse_program("const MAX = 1\nvar MAX");
+        assert_eq!(result, Err("Cannot assign to constant: MAX at line 2".to_string()));
+    }
+
+    // ==================== Parentheses Tests ====================
+
+    #[test]
+    fn test_parentheses_override_precedence() {
+        let program = parse_program("(1 + 2) * 3").unwrap();
+        assert_eq!(program.statements[0], Statement::Expr(Expr::BinaryOp {
+            left: Box::new(Expr::BinaryOp {
+                left: Box::new(Expr::Number(1)),
+                operator: Operator::Plus,
+                right: Box::new(Expr::Number(2)),
+            }),
+            operator: Operator::Multiply,
+            right: Box::new(Expr::Number(3)),
+        }));
+    }
+
+    #[test]
+    fn test_parentheses_right_operand() {
+        let program = parse_program("x = a - (b - c)").unwrap();
+        assert_eq!(program.statements[0], Statement::Assign {
+            name: "x".to_string(),
+            value: Expr::BinaryOp {
+                left: Box::new(Expr::Identifier("a".to_string())),
+                operator: Operator::Minus,
+                right: Box::new(Expr::BinaryOp {
+                    left: Box::new(Expr::Identifier("b".to_string())),
+                    operator: Operator::Minus,
+                    right: Box::new(Expr::Identifier("c".to_string())),
+                }),
+            },
+        });
+    }
+
+    #[test]
+    fn test_unclosed_parenthesis_error() {
+        let result = parse_program("x = (1 + 2");
+        assert_eq!(result, Err("Expected ) after expression, found: EOF at line 1".to_string()));
+    }
+
+    #[test]
+    fn test_comments_ignored() {
+        let program = parse_program("# set x\nx = 1 # one\n").unwrap();
+        assert_eq!(program.statements.len(), 1);
+        assert_eq!(program.lines, vec![2]);
+    }
+
+    // ==================== Call Tests ====================
+
+    #[test]
+    fn test_call_with_arguments() {
+        let program = parse_program("x = max(a, 2 * 3) + 1").unwrap();
+        assert_eq!(program.statements[0], Statement::Assign {
+            name: "x".to_string(),
+            value: Expr::BinaryOp {
+                left: Box::new(Expr::Call {
+                    name: "max".to_string(),
+                    args: vec![
+                        Expr::Identifier("a".to_string()),
+                        Expr::BinaryOp {
+                            left: Box::new(Expr::Number(2)),
+                            operator: Operator::Multiply,
+                            right: Box::new(Expr::Number(3)),
+                        },
+                    ],
+                }),
+                operator: Operator::Plus,
+                right: Box::new(Expr::Number(1)),
+            },
+        });
+    }
+
+    #[test]
+    fn test_call_statement() {
+        let program = parse_program("show()\nf(x) + 1").unwrap();
+        assert_eq!(program.statements, vec![
+            Statement::Expr(Expr::Call { name: "show".to_string(), args: Vec::new() }),
+            Statement::Expr(Expr::BinaryOp {
+                left: Box::new(Expr::Call {
+                    name: "f".to_string(),
+                    args: vec![Expr::Identifier("x".to_string())],
+                }),
+                operator: Operator::Plus,
+                right: Box::new(Expr::Number(1)),
+            }),
+        ]);
+        assert_eq!(program.lines, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_parenthesis_on_next_line_is_not_a_call() {
+        let program = parse_program("x = y\n(x > 1)").unwrap();
+        assert_eq!(program.statements.len(), 2);
+        assert_eq!(program.statements[0], Statement::Assign {
+            name: "x".to_string(),
+            value: Expr::Identifier("y".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_unclosed_call_error() {
+        assert_eq!(
+            parse_program("x = f(1 2)"),
+            Err("Expected , or ) in call to f, found: Number(2) at line 1".to_string())
+        );
+    }
+}
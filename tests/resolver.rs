@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Statement};
+    use pseudocu::lexer::tokenize;
+    use pseudocu::parser::Parser;
+    use pseudocu::resolver::{resolve, Diagnostic, Resolver, Severity};
+
+    fn resolve_source(input: &str) -> Vec<Diagnostic> {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        resolve(&parser.parse().unwrap())
+    }
+
+    fn errors(diagnostics: &[Diagnostic]) -> Vec<String> {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    fn warnings(diagnostics: &[Diagnostic]) -> Vec<String> {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    // ==================== Use Before Assignment Tests ====================
+
+    #[test]
+    fn test_defined_variables_have_no_errors() {
+        let diagnostics = resolve_source("d = 5\na = 4\nresult = d + a");
+        assert!(errors(&diagnostics).is_empty());
+    }
+
+    #[test]
+    fn test_undefined_variable() {
+        let diagnostics = resolve_source("x = 1\ny = x + z");
+        assert_eq!(errors(&diagnostics), vec!["Undefined variable: z at line 2"]);
+    }
+
+    #[test]
+    fn test_use_before_assignment() {
+        let diagnostics = resolve_source("y = x\nx = 1\ny = x");
+        assert_eq!(errors(&diagnostics), vec!["Undefined variable: x at line 1"]);
+    }
+
+    #[test]
+    fn test_uninitialized_declaration() {
+        let diagnostics = resolve_source("var x: entero\ny = x\nx = 2\nz = x + y");
+        assert_eq!(errors(&diagnostics), vec!["Uninitialized variable: x at line 2"]);
+    }
+
+    #[test]
+    fn test_compound_assignment_on_undefined_variable() {
+        let diagnostics = resolve_source("total += 1");
+        assert_eq!(errors(&diagnostics), vec!["Undefined variable: total at line 1"]);
+    }
+
+    #[test]
+    fn test_redefinition() {
+        let diagnostics = resolve_source("x = 1\nvar x\ny = x");
+        assert_eq!(errors(&diagnostics), vec!["Variable already defined: x at line 2"]);
+    }
+
+    #[test]
+    fn test_strict_mode_undeclared_assignment() {
+        let tokens = tokenize("var x = 1\ny = x".to_string()).unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut resolver = Resolver::new();
+        resolver.set_strict_mode(true);
+        let diagnostics = resolver.resolve(&program);
+        assert_eq!(errors(&diagnostics), vec!["Undeclared variable: y at line 2"]);
+    }
+
+    // ==================== Warning Tests ====================
+
+    #[test]
+    fn test_unused_globals_are_not_reported() {
+        let diagnostics = resolve_source("a = 1\nb = 2\nc = a\nvar d");
+        assert!(warnings(&diagnostics).is_empty());
+    }
+
+    #[test]
+    fn test_unused_variables_in_nested_scope() {
+        let mut resolver = Resolver::new();
+        resolver.begin_scope();
+        for name in ["a", "b"] {
+            resolver.resolve_statement(&Statement::Assign {
+                name: name.to_string(),
+                value: Expr::Number(1),
+            });
+        }
+        resolver.resolve_expr(&Expr::Identifier("a".to_string()));
+        resolver.end_scope();
+        assert_eq!(warnings(&resolver.finish()), vec!["Unused variable: b"]);
+    }
+
+    #[test]
+    fn test_compound_assignment_counts_as_use() {
+        let diagnostics = resolve_source("total = 0\ntotal += 5");
+        assert!(warnings(&diagnostics).is_empty());
+    }
+
+    #[test]
+    fn test_shadowing_in_nested_scope() {
+        let mut resolver = Resolver::new();
+        resolver.resolve_statement(&Statement::Assign {
+            name: "x".to_string(),
+            value: Expr::Number(1),
+        });
+        resolver.begin_scope();
+        resolver.resolve_statement(&Statement::Declare {
+            name: "x".to_string(),
+            type_annotation: None,
+            value: Some(Expr::Identifier("x".to_string())),
+        });
+        resolver.resolve_expr(&Expr::Identifier("x".to_string()));
+        resolver.end_scope();
+        let diagnostics = resolver.finish();
+
+        assert_eq!(
+            warnings(&diagnostics),
+            vec!["Declaration of x shadows an outer variable"]
+        );
+        assert!(errors(&diagnostics).is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics_ordered_by_line() {
+        let diagnostics = resolve_source("a = 1\nb = c + e\nd = f");
+        let lines: Vec<Option<usize>> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![Some(2), Some(2), Some(3)]);
+    }
+}
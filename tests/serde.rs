@@ -0,0 +1,78 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Operator, Program, Statement, Type};
+    use pseudocu::lexer::{tokenize, Token};
+    use pseudocu::parser::Parser;
+
+    const SOURCE: &str = "\
+const LIMIT = 100
+var total: entero = 0
+var done: booleano
+var spare
+total += LIMIT * (2 - 1)
+total -= 1
+total *= 3
+total /= 2
+done = total >= LIMIT
+(total != 0) == (1 < 2)
+(total > 1) <= (total == 2)
+";
+
+    fn parse(source: &str) -> Program {
+        Parser::new(tokenize(source.to_string()).unwrap()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_program_round_trip() {
+        let program = parse(SOURCE);
+        let json = serde_json::to_string(&program).unwrap();
+        let back: Program = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, program);
+    }
+
+    #[test]
+    fn test_tokens_round_trip() {
+        let tokens = tokenize(SOURCE.to_string()).unwrap();
+        let json = serde_json::to_string(&tokens).unwrap();
+        let back: Vec<Token> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, tokens);
+    }
+
+    #[test]
+    fn test_expr_round_trip_extremes() {
+        let expr = Expr::BinaryOp {
+            left: Box::new(Expr::Number(i64::MIN)),
+            operator: Operator::Divide,
+            right: Box::new(Expr::Number(i64::MAX)),
+        };
+        let back: Expr = serde_json::from_str(&serde_json::to_string(&expr).unwrap()).unwrap();
+        assert_eq!(back, expr);
+    }
+
+    #[test]
+    fn test_serialized_shape() {
+        let program = Program {
+            statements: vec![
+                Statement::Declare {
+                    name: "x".to_string(),
+                    type_annotation: Some(Type::Integer),
+                    value: Some(Expr::Number(1)),
+                },
+                Statement::Expr(Expr::Identifier("x".to_string())),
+            ],
+            lines: vec![1, 2],
+        };
+        assert_eq!(
+            serde_json::to_string(&program).unwrap(),
+            r#"{"statements":[{"Declare":{"name":"x","type_annotation":"Integer","value":{"Number":1}}},{"Expr":{"Identifier":"x"}}],"lines":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_is_rejected() {
+        assert!(serde_json::from_str::<Program>(r#"{"statements":[{"Loop":{}}],"lines":[]}"#).is_err());
+        assert!(serde_json::from_str::<Expr>(r#"{"Number":"one"}"#).is_err());
+    }
+}
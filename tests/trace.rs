@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Program, Statement};
+    use pseudocu::interpreter::Interpreter;
+    use pseudocu::lexer::tokenize;
+    use pseudocu::parser::Parser;
+    use pseudocu::trace::{Trace, TraceFormat, TraceRow};
+
+    const SOURCE: &str = "x = 2\nvar y\n\ny = x * 3\nconst K = 100\ny += K\n";
+
+    fn trace(source: &str) -> (Trace, Result<(), String>) {
+        let tokens = tokenize(source.to_string()).unwrap();
+        let program = Parser::new(tokens).parse().unwrap();
+        let mut trace = Trace::new();
+        let result = Interpreter::new().run_with_hook(&program, &mut trace);
+        (trace, result)
+    }
+
+    // ==================== Recording Tests ====================
+
+    #[test]
+    fn test_one_row_per_statement() {
+        let (trace, result) = trace(SOURCE);
+        assert!(result.is_ok());
+        let lines: Vec<_> = trace.rows.iter().map(|row| row.line).collect();
+        assert_eq!(lines, vec![Some(1), Some(2), Some(4), Some(5), Some(6)]);
+        assert_eq!(trace.rows[2], TraceRow {
+            line: Some(4),
+            variables: vec![("x".to_string(), 2), ("y".to_string(), 6)],
+        });
+        assert_eq!(trace.columns(), vec!["x", "y", "K"]);
+    }
+
+    #[test]
+    fn test_statements_on_one_line_get_separate_rows() {
+        let (trace, _) = trace("a = 1 b = a + 1\n");
+        assert_eq!(trace.render(TraceFormat::Csv), "Line,a,b\n1,1,\n1,1,2\n");
+    }
+
+    #[test]
+    fn test_row_order_differs_from_columns() {
+        let (trace, _) = trace("var a: entero\nb = 1\na = 2\n");
+        assert_eq!(trace.rows[2].variables, vec![("a".to_string(), 2), ("b".to_string(), 1)]);
+        assert_eq!(trace.columns(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_trace_stops_at_runtime_error() {
+        let (trace, result) = trace("x = 1\ny = x / 0\nz = 3\n");
+        assert_eq!(result, Err("Division by zero".to_string()));
+        assert_eq!(trace.render(TraceFormat::Csv), "Line,x\n1,1\n");
+    }
+
+    #[test]
+    fn test_rows_without_lines() {
+        let program = Program {
+            statements: vec![Statement::Assign { name: "x".to_string(), value: Expr::Number(7) }],
+            lines: Vec::new(),
+        };
+        let mut trace = Trace::new();
+        Interpreter::new().run_with_hook(&program, &mut trace).unwrap();
+        assert_eq!(trace.render(TraceFormat::Csv), "Line,x\n,7\n");
+    }
+
+    // ==================== Rendering Tests ====================
+
+    #[test]
+    fn test_render_text() {
+        let (trace, _) = trace(SOURCE);
+        assert_eq!(
+            trace.render(TraceFormat::Text),
+            "Line | x |   y |   K\n\
+             -----+---+-----+----\n   \
+                1 | 2 |     |\n   \
+                2 | 2 |     |\n   \
+                4 | 2 |   6 |\n   \
+                5 | 2 |   6 | 100\n   \
+                6 | 2 | 106 | 100\n"
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let (trace, _) = trace(SOURCE);
+        assert_eq!(
+            trace.render(TraceFormat::Csv),
+            "Line,x,y,K\n1,2,,\n2,2,,\n4,2,6,\n5,2,6,100\n6,2,106,100\n"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let (trace, _) = trace("x = 2\ny = x * 3\n");
+        assert_eq!(
+            trace.render(TraceFormat::Markdown),
+            "| Line |   x |   y |\n\
+             | ---: | --: | --: |\n\
+             |    1 |   2 |     |\n\
+             |    2 |   2 |   6 |\n"
+        );
+    }
+
+    #[test]
+    fn test_render_empty_program() {
+        let (trace, _) = trace("");
+        assert_eq!(trace.render(TraceFormat::Text), "Line\n----\n");
+        assert_eq!(trace.render(TraceFormat::Csv), "Line\n");
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(TraceFormat::parse("text"), Some(TraceFormat::Text));
+        assert_eq!(TraceFormat::parse("csv"), Some(TraceFormat::Csv));
+        assert_eq!(TraceFormat::parse("markdown"), Some(TraceFormat::Markdown));
+        assert_eq!(TraceFormat::parse("md"), Some(TraceFormat::Markdown));
+        assert_eq!(TraceFormat::parse("xml"), None);
+    }
+}
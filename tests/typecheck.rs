@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Operator, Program, Statement, Type};
+    use pseudocu::lexer::tokenize;
+    use pseudocu::parser::Parser;
+    use pseudocu::typecheck::{check, TypeChecker, TypeError};
+
+    fn parse_program(input: &str) -> Program {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse().unwrap()
+    }
+
+    fn check_source(input: &str) -> Result<(), Vec<TypeError>> {
+        check(&parse_program(input))
+    }
+
+    // ==================== Valid Program Tests ====================
+
+    #[test]
+    fn test_untyped_program_is_valid() {
+        assert_eq!(check_source("d = 5\na = 4\nresult = d + a"), Ok(()));
+    }
+
+    #[test]
+    fn test_boolean_accepts_comparison() {
+        assert_eq!(check_source("var listo: booleano\nx = 10\nlisto = x > 5"), Ok(()));
+    }
+
+    #[test]
+    fn test_boolean_accepts_zero_and_one_literals() {
+        assert_eq!(check_source("var a: booleano = 0\nvar b: booleano = 1"), Ok(()));
+    }
+
+    #[test]
+    fn test_integer_accepts_boolean() {
+        assert_eq!(check_source("var n: entero = 3 == 3"), Ok(()));
+    }
+
+    #[test]
+    fn test_boolean_accepts_variable_holding_boolean() {
+        assert_eq!(check_source("var flag: booleano\nx = 1 < 2\nflag = x"), Ok(()));
+    }
+
+    // ==================== Type Error Tests ====================
+
+    #[test]
+    fn test_boolean_rejects_integer_literal() {
+        assert_eq!(
+            check_source("var flag: booleano\nflag = 5"),
+            Err(vec![TypeError {
+                message: "Type mismatch: cannot assign entero to flag of type booleano".to_string(),
+                line: Some(2),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_boolean_rejects_arithmetic() {
+        let errors = check_source("var flag: booleano = 1\nflag += 1").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_reports_all_errors() {
+        let errors = check_source(
+            "var a: booleano = 2\nx = 3 + 4\nvar b: booleano\nb = x\na = 1"
+        ).unwrap_err();
+        let lines: Vec<Option<usize>> = errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![Some(1), Some(4)]);
+    }
+
+    #[test]
+    fn test_error_display_includes_line() {
+        let errors = check_source("x = 1\nvar flag: booleano = x + 1").unwrap_err();
+        assert_eq!(
+            errors[0].to_string(),
+            "Type mismatch: cannot assign entero to flag of type booleano at line 2"
+        );
+    }
+
+    #[test]
+    fn test_hand_built_program_without_lines() {
+        let program = Program {
+            statements: vec![
+                Statement::Declare {
+                    name: "flag".to_string(),
+                    type_annotation: Some(Type::Boolean),
+                    value: Some(Expr::Number(7)),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        let errors = check(&program).unwrap_err();
+        assert_eq!(errors[0].line, None);
+    }
+
+    // ==================== Inference Tests ====================
+
+    #[test]
+    fn test_infers_variable_types() {
+        let program = parse_program("a = 5\nb = a > 2\nc = b * 2\nvar d: booleano");
+        let mut checker = TypeChecker::new();
+        assert_eq!(checker.check(&program), Ok(()));
+        assert_eq!(checker.variable_type("a"), Some(Type::Integer));
+        assert_eq!(checker.variable_type("b"), Some(Type::Boolean));
+        assert_eq!(checker.variable_type("c"), Some(Type::Integer));
+        assert_eq!(checker.variable_type("d"), Some(Type::Boolean));
+        assert_eq!(checker.variable_type("missing"), None);
+    }
+
+    #[test]
+    fn test_infer_expression() {
+        let checker = TypeChecker::new();
+        let comparison = Expr::BinaryOp {
+            left: Box::new(Expr::Number(2)),
+            operator: Operator::LtEq,
+            right: Box::new(Expr::Number(3)),
+        };
+        assert_eq!(checker.infer_expr(&comparison), Some(Type::Boolean));
+        assert_eq!(checker.infer_expr(&Expr::Number(42)), Some(Type::Integer));
+        assert_eq!(checker.infer_expr(&Expr::Identifier("x".to_string())), None);
+    }
+
+    // ==================== Boolean Arithmetic Tests ====================
+
+    #[test]
+    fn test_boolean_accepts_arithmetic_with_boolean_result() {
+        assert_eq!(check_source("var f: booleano = 1 * 1"), Ok(()));
+        assert_eq!(check_source("var f: booleano = 1\nf *= 1\nf -= 1\nf = 3 - 2"), Ok(()));
+    }
+
+    #[test]
+    fn test_boolean_arithmetic_with_unknown_result_is_not_rejected() {
+        assert_eq!(check_source("var a: booleano = x > 1\nvar b: booleano = a\nb *= a"), Ok(()));
+        let mut checker = TypeChecker::new();
+        checker.check(&parse_program("var a: booleano = x > 1\nc = a * a\nd = a * 2")).unwrap();
+        assert_eq!(checker.variable_type("c"), None);
+        assert_eq!(checker.variable_type("d"), Some(Type::Integer));
+    }
+
+    #[test]
+    fn test_arithmetic_on_calls_is_not_rejected() {
+        assert_eq!(check_source("var b: booleano = f(1)"), Ok(()));
+        assert_eq!(check_source("var c: booleano = f(1) * 1"), Ok(()));
+        assert_eq!(check_source("var d: booleano = f(1) + 0"), Ok(()));
+        assert_eq!(check_source("var e: booleano = f(1) * g(2)"), Ok(()));
+        assert!(check_source("var i: booleano = f(1) * 5").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use pseudocu::ast::{Expr, Program, Statement};
+    use pseudocu::compiler::{compile, Instruction};
+    use pseudocu::interpreter::Interpreter;
+    use pseudocu::lexer::tokenize;
+    use pseudocu::output::SharedBuffer;
+    use pseudocu::parser::Parser;
+    use pseudocu::vm::Vm;
+
+    fn parse_program(input: &str) -> Program {
+        let tokens = tokenize(input.to_string()).unwrap();
+        let mut parser = Parser::new(tokens);
+        parser.parse().unwrap()
+    }
+
+    /// Runs a program on both the tree-walking interpreter and the VM and
+    /// checks that they finish with the same result, variable values and
+    /// printed variable table.
+    fn assert_parity(program: &Program, strict: bool) {
+        let interpreter_output = SharedBuffer::new();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_strict_mode(strict);
+        interpreter.set_output(interpreter_output.clone());
+        let interpreter_result = interpreter.run(program);
+        interpreter.print_variables().unwrap();
+
+        let vm_output = SharedBuffer::new();
+        let chunk = compile(program);
+        let mut vm = Vm::new();
+        vm.set_strict_mode(strict);
+        vm.set_output(vm_output.clone());
+        let vm_result = vm.run(&chunk);
+        vm.print_variables().unwrap();
+
+        assert_eq!(vm_result, interpreter_result);
+        assert_eq!(vm_output.contents(), interpreter_output.contents());
+        for name in &chunk.names {
+            assert_eq!(
+                vm.get_variable(name),
+                interpreter.get_variable(name),
+                "variable {} differs",
+                name
+            );
+        }
+    }
+
+    fn assert_source_parity(input: &str) {
+        assert_parity(&parse_program(input), false);
+    }
+
+    // ==================== Compiler Tests ====================
+
+    #[test]
+    fn test_compile_assignment() {
+        let chunk = compile(&parse_program("x = 1 + 2"));
+        assert_eq!(chunk.constants, vec![1, 2]);
+        assert_eq!(chunk.names, vec!["x".to_string()]);
+        assert_eq!(
+            chunk.code,
+            vec![
+                Instruction::CheckAssignable(0),
+                Instruction::Constant(0),
+                Instruction::Constant(1),
+                Instruction::Add,
+                Instruction::Store(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_constant_pool_deduplicates_values() {
+        let chunk = compile(&parse_program("a = 7\nb = 7 * 7\nc = a"));
+        assert_eq!(chunk.constants, vec![7]);
+        assert_eq!(chunk.names, vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn test_variables_share_slots() {
+        let chunk = compile(&parse_program("x = 1\nx += x"));
+        assert_eq!(chunk.names, vec!["x".to_string()]);
+        assert_eq!(
+            chunk.code[3..],
+            [
+                Instruction::CheckAssignable(0),
+                Instruction::Load(0),
+                Instruction::Load(0),
+                Instruction::Add,
+                Instruction::Store(0),
+            ]
+        );
+    }
+
+    // ==================== VM Tests ====================
+
+    #[test]
+    fn test_vm_runs_program() {
+        let chunk = compile(&parse_program("d = 5\na = 4\nresult = d + a"));
+        let mut vm = Vm::new();
+        assert_eq!(vm.run(&chunk), Ok(()));
+        assert_eq!(vm.get_variable("result"), Some(9));
+        assert_eq!(vm.get_variable("missing"), None);
+    }
+
+    #[test]
+    fn test_vm_can_rerun_chunk() {
+        let chunk = compile(&parse_program("x = 2\nx *= 21"));
+        let mut vm = Vm::new();
+        vm.run(&chunk).unwrap();
+        vm.run(&chunk).unwrap();
+        assert_eq!(vm.get_variable("x"), Some(42));
+    }
+
+    // ==================== Parity Tests ====================
+
+    #[test]
+    fn test_parity_arithmetic_and_comparisons() {
+        assert_source_parity(
+            "a = 7\nb = 0 - 3\nc = a / b\nd = a * b - c + 1\ne = a > b\nf = a <= b\ng = a == 7\nh = b != 0\ni = a >= 7\nj = b < a"
+        );
+    }
+
+    #[test]
+    fn test_parity_compound_and_declarations() {
+        assert_source_parity(
+            "const K = 3\nvar total: entero = 0\ntotal += K\ntotal *= 4\ntotal -= 2\ntotal /= 5\nvar flag: booleano\nflag = total > 1"
+        );
+    }
+
+    #[test]
+    fn test_parity_runtime_errors() {
+        assert_source_parity("x = 10\ny = x / 0\nz = 1");
+        assert_source_parity("x = 1\ny = missing + x");
+        assert_source_parity("var x\ny = x");
+        assert_source_parity("var flag: booleano = 1\nflag += 1");
+        assert_source_parity("x = 9223372036854775807\nx += 1");
+        assert_source_parity("x = 1\nvar x = 2");
+    }
+
+    #[test]
+    fn test_parity_constant_errors() {
+        let assign_to_constant = Program {
+            statements: vec![
+                Statement::Const {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(10),
+                },
+                Statement::Assign {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(20),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        assert_parity(&assign_to_constant, false);
+
+        let redefine_constant = Program {
+            statements: vec![
+                Statement::Const {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(10),
+                },
+                Statement::Const {
+                    name: "MAX".to_string(),
+                    value: Expr::Number(20),
+                },
+            ],
+            lines: Vec::new(),
+        };
+        assert_parity(&redefine_constant, false);
+    }
+
+    #[test]
+    fn test_parity_strict_mode() {
+        assert_parity(&parse_program("var x = 1\nx += 1\ny = x"), true);
+        assert_parity(&parse_program("const K = 1\nvar x: entero\nx = K"), true);
+    }
+
+    // ==================== Call Tests ====================
+
+    #[test]
+    fn test_compile_call() {
+        let chunk = compile(&parse_program("f(1, g())\nf(2)"));
+        assert_eq!(chunk.functions, vec!["g".to_string(), "f".to_string()]);
+        assert_eq!(
+            chunk.code[..4],
+            [
+                Instruction::Constant(0),
+                Instruction::Call { function: 0, args: 0 },
+                Instruction::Call { function: 1, args: 2 },
+                Instruction::Pop,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parity_calls() {
+        let program = parse_program("x = 7\ny = sub(x, 2) * 3\nz = sub(y, x)");
+        let mut interpreter = Interpreter::new();
+        interpreter.register_function("sub", |args| Ok(args[0] - args[1]));
+        interpreter.run(&program).unwrap();
+        let mut vm = Vm::new();
+        vm.register_function("sub", |args| Ok(args[0] - args[1]));
+        vm.run(&compile(&program)).unwrap();
+        for name in ["x", "y", "z"] {
+            assert_eq!(vm.get_variable(name), interpreter.get_variable(name));
+        }
+        assert_eq!(vm.get_variable("z"), Some(8));
+
+        assert_parity(&parse_program("x = f(1)"), false);
+        assert_parity(&parse_program("x = 1\nf(x / 0)"), false);
+    }
+}